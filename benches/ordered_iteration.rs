@@ -0,0 +1,41 @@
+//! Leaf tasks of a 10k task project in id order, from either store. The ordered one walks its map
+//! as is, while the hashed one collects and sorts the tasks on every pass
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use aplan::prelude::{Project, TaskId};
+
+const PHASES: u32 = 100;
+const STEPS: u32 = 99;
+const PASSES: u32 = 100;
+
+fn project(ordered: bool) -> Project {
+    let mut project = if ordered { Project::new_ordered("Bench") } else { Project::new("Bench") };
+    project.tasks_mut(|tasks| {
+        for phase in 1..=PHASES {
+            tasks.add(TaskId::get_root_id(), "Phase")?;
+            for _ in 0..STEPS {
+                tasks.add(TaskId::new(vec![phase]), "Step")?;
+            }
+        }
+        Ok(())
+    }).unwrap();
+    project
+}
+
+/// Mean time of a pass over the leaf tasks
+fn time_leaf_pass(project: &Project) -> Duration {
+    let start = Instant::now();
+    for _ in 0..PASSES {
+        black_box(project.tasks().get_tasks().count());
+    }
+    start.elapsed() / PASSES
+}
+
+fn main() {
+    for (label, ordered) in [("ordered", true), ("hashed", false)] {
+        let project = project(ordered);
+        assert_eq!(project.tasks().len(), (PHASES * (STEPS + 1) + 1) as usize);
+        println!("{:>8}: {:?} per pass over {} leaf tasks", label, time_leaf_pass(&project), PHASES * STEPS);
+    }
+}
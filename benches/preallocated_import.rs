@@ -0,0 +1,41 @@
+//! Building a 100k task project, with the task map preallocated through `Project::with_capacity`
+//! and growing from empty through `Project::new`
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use aplan::prelude::{Project, TaskId};
+
+const PHASES: u32 = 1_000;
+const STEPS: u32 = 99;
+const TASKS: usize = (PHASES * (STEPS + 1)) as usize;
+const RUNS: u32 = 5;
+
+fn import(mut project: Project) -> Project {
+    project.tasks_mut(|tasks| {
+        for phase in 1..=PHASES {
+            tasks.add(TaskId::get_root_id(), "Phase")?;
+            for _ in 0..STEPS {
+                tasks.add(TaskId::new(vec![phase]), "Step")?;
+            }
+        }
+        Ok(())
+    }).unwrap();
+    project
+}
+
+/// Mean time of an import
+fn time_import(new_project: impl Fn() -> Project) -> Duration {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        let project = black_box(import(new_project()));
+        assert_eq!(project.tasks().len(), TASKS + 1);
+    }
+    start.elapsed() / RUNS
+}
+
+fn main() {
+    let preallocated = time_import(|| Project::with_capacity("Bench", TASKS + 1));
+    let growing = time_import(|| Project::new("Bench"));
+    println!("preallocated: {:?} per import of {} tasks", preallocated, TASKS);
+    println!("     growing: {:?} per import of {} tasks", growing, TASKS);
+}
@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{io::IsTerminal, str::FromStr};
 
 use chrono::{Utc, NaiveDateTime, NaiveDate};
 use clap::{Parser, Subcommand};
@@ -39,6 +39,7 @@ enum Commands {
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
 enum ShowFormat {
     Dot,
+    Dependencies,
     Text
 }
 
@@ -48,6 +49,7 @@ impl FromStr for ShowFormat {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "dot" => Ok(ShowFormat::Dot),
+            "dependencies" | "deps" => Ok(ShowFormat::Dependencies),
             "text" | "txt" | "tree" => Ok(ShowFormat::Text),
             _ => Err(Error::ParseCliArgument(s.to_string()))
         }
@@ -181,6 +183,27 @@ enum TaskCommands {
         id: TaskId,
         #[clap(value_parser = task_id_parser)]
         dependency: TaskId
+    },
+    /// Rename a task. Renaming the root renames the project
+    Rename {
+        /// Task id
+        #[clap(value_parser = task_id_parser)]
+        id: TaskId,
+        /// New name of the task
+        #[clap(value_parser)]
+        name: String
+    },
+    /// Move a task, along with its subtasks, under another parent
+    Move {
+        /// Id of the task to move
+        #[clap(value_parser = task_id_parser)]
+        id: TaskId,
+        /// Id of the new parent
+        #[clap(value_parser = task_id_parser)]
+        new_parent: TaskId,
+        /// Keep the task's index among its new siblings, if possible
+        #[clap(short, long)]
+        keep_index: bool,
     }
 }
 
@@ -200,7 +223,11 @@ fn process_tasks(command: &TaskCommands, project_filename: &str) -> Result<Proje
         TaskCommands::Show { format, output } => {
             match format {
                 ShowFormat::Dot => util::to_file(output.as_deref(), project.tasks().to_dot_str())?,
-                ShowFormat::Text => util::to_file(output.as_deref(), project.tasks().to_tree_str())?,
+                ShowFormat::Dependencies => util::to_file(output.as_deref(), project.tasks().to_dependency_dot_str())?,
+                ShowFormat::Text => {
+                    let color = matches!(output.as_deref(), Some("-") | None) && std::io::stdout().is_terminal();
+                    util::to_file(output.as_deref(), project.tasks().to_tree_str_colored(color))?
+                },
             }
         },
         TaskCommands::Add { parent, name } => {
@@ -250,6 +277,18 @@ fn process_tasks(command: &TaskCommands, project_filename: &str) -> Result<Proje
                 Ok(())
             })?;
         },
+        TaskCommands::Rename { id, name } => {
+            project.tasks_mut(|tasks| {
+                tasks.rename(id, name)?;
+                Ok(())
+            })?;
+        },
+        TaskCommands::Move { id, new_parent, keep_index } => {
+            project.tasks_mut(|tasks| {
+                println!("{}", tasks.reparent(id, new_parent, *keep_index)?);
+                Ok(())
+            })?;
+        },
     };
     Ok(project)
 }
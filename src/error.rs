@@ -45,11 +45,8 @@ pub enum Error {
     #[error("Can't convert '{0}' to trunk because it has or is a dependency")]
     CannotConvertToTrunk(TaskId),
 
-    #[error("Cannot remove node '{0}' with dependencies")]
-    CannotRemoveWithDependency(TaskId),
-
-    #[error("Creating this dependency edge ['{0}' -> '{1}'] would create a cycle")]
-    EdgeCreationLeadsToCycle(TaskId, TaskId),
+    #[error("The dependency of '{0}' on '{1}' closes a cycle")]
+    DependencyCycle(TaskId, TaskId),
 
     #[error("Root task '{0}' doesn't have a parent")]
     NoParent(TaskId),
@@ -69,6 +66,9 @@ pub enum Error {
     #[error("Can't remove members from trunk tasks like '{0}' directly")]
     TrunkCannotRemoveMember(TaskId),
 
+    #[error("Can't change the status of '{0}' directly, only that of leaf tasks")]
+    CannotMarkTrunk(TaskId),
+
     #[error("Can't remove tasks with members assigned to it")]
     CannotRemoveAssignedTask(TaskId),
 
@@ -86,4 +86,40 @@ pub enum Error {
 
     #[error("There is no prev sibling for task with id: '{0}'")]
     NoPrevSibling(TaskId),
+
+    #[error("Task stored under id '{0}' has a different id")]
+    MismatchedTaskId(TaskId),
+
+    #[error("Task '{0}' isn't reachable from its parent")]
+    OrphanTask(TaskId),
+
+    #[error("Task '{0}' should have child '{1}', but it doesn't exist")]
+    MissingChild(TaskId, TaskId),
+
+    #[error("Dependency between '{0}' and '{1}' isn't recorded on both tasks")]
+    DanglingDependency(TaskId, TaskId),
+
+    #[error("Can't move '{0}' under itself or its descendant '{1}'")]
+    CannotMoveIntoDescendant(TaskId, TaskId),
+
+    #[error("Values can only be scaled by a finite, non-negative factor")]
+    InvalidScaleFactor,
+
+    #[error("Task '{0}' can't depend on itself")]
+    SelfDependency(TaskId),
+
+    #[error("Can't give task '{0}' an empty name")]
+    EmptyTaskName(TaskId),
+
+    #[error("Task '{0}' needs both a start and a due date")]
+    MissingDates(TaskId),
+
+    #[error("Task '{0}' is due before it starts")]
+    InvalidDateRange(TaskId),
+
+    #[error("Task '{0}' isn't done")]
+    TaskNotDone(TaskId),
+
+    #[error("Malformed CSV row at line {0}")]
+    BadCsvRow(usize),
 }
@@ -1,4 +1,8 @@
-use crate::{task::task_id::TaskId, project::Project, error::Error};
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+
+use crate::{task::{Task, task_id::TaskId, tasks::Tasks, metrics::EvMode, TaskStatus}, project::Project, error::Error};
 
 #[derive(Debug)]
 pub struct TaskExecution<'a> {
@@ -28,11 +32,28 @@ impl<'a> TaskExecution<'a> {
         Ok(self)
     }
 
+    pub fn chain_dependencies(&mut self, ids: &[TaskId]) -> Result<&mut Self, Error> {
+        self.project.tasks.chain_dependencies(ids)?;
+        Ok(self)
+    }
+
+    pub fn minimize_dependencies(&mut self) -> Result<usize, Error> {
+        self.project.tasks.minimize_dependencies()
+    }
+
     pub fn remove(&mut self, id: &TaskId) -> Result<&mut Self, Error> {
-        self.project.tasks.remove(id, &self.project.members)?;
+        self.project.tasks.remove(id, &mut self.project.members)?;
         Ok(self)
     }
 
+    pub fn remove_subtree(&mut self, id: &TaskId) -> Result<Vec<Task>, Error> {
+        self.project.tasks.remove_subtree(id, &mut self.project.members)
+    }
+
+    pub fn renumber_batch(&mut self, ids: &[TaskId]) -> Result<HashMap<TaskId, TaskId>, Error> {
+        self.project.tasks.renumber_batch(ids, &mut self.project.members)
+    }
+
     pub fn expand<const N: usize>(&mut self, arr: &[(&str, &str); N]) -> Result<&mut Self, Error> {
         self.project.tasks.expand(arr)?;
         Ok(self)
@@ -43,8 +64,93 @@ impl<'a> TaskExecution<'a> {
         Ok(self)
     }
 
+    pub fn mark_done(&mut self, id: &TaskId) -> Result<&mut Self, Error> {
+        self.project.tasks.mark_done(id)?;
+        Ok(self)
+    }
+
+    pub fn reopen(&mut self, id: &TaskId) -> Result<&mut Self, Error> {
+        self.project.tasks.reopen(id)?;
+        Ok(self)
+    }
+
+    pub fn progress(&mut self, id: &TaskId, progress: f64) -> Result<&mut Self, Error> {
+        self.project.tasks.set_progress(id, progress)?;
+        Ok(self)
+    }
+
+    pub fn apply_actual_costs(&mut self, costs: &HashMap<TaskId, f64>) -> Result<Vec<TaskId>, Error> {
+        self.project.tasks.apply_actual_costs(costs)
+    }
+
     pub fn planned_value(&mut self, id: &TaskId, planned_value: f64) -> Result<&mut Self, Error> {
         self.project.tasks.set_planned_value(id, planned_value)?;
         Ok(self)
     }
+
+    pub fn apply_status_updates(&mut self, updates: &HashMap<TaskId, TaskStatus>) -> Result<Vec<TaskId>, Error> {
+        self.project.tasks.apply_status_updates(updates)
+    }
+
+    pub fn reparent(&mut self, id: &TaskId, new_parent: &TaskId, keep_index: bool) -> Result<TaskId, Error> {
+        self.project.tasks.reparent(id, new_parent, keep_index, &mut self.project.members)
+    }
+
+    pub fn align_ids_to(&mut self, other: &Tasks) -> Result<HashMap<TaskId, TaskId>, Error> {
+        self.project.tasks.align_ids_to(other, &mut self.project.members)
+    }
+
+    pub fn rename(&mut self, id: &TaskId, name: &str) -> Result<&mut Self, Error> {
+        self.project.tasks.rename_task(id, name)?;
+        Ok(self)
+    }
+
+    pub fn rename_many(&mut self, renames: &HashMap<TaskId, String>) -> Result<usize, Error> {
+        self.project.tasks.rename_many(renames)
+    }
+
+    pub fn description(&mut self, id: &TaskId, description: Option<&str>) -> Result<&mut Self, Error> {
+        self.project.tasks.set_description(id, description)?;
+        Ok(self)
+    }
+
+    pub fn start_date(&mut self, id: &TaskId, date: Option<NaiveDate>) -> Result<&mut Self, Error> {
+        self.project.tasks.set_start_date(id, date)?;
+        Ok(self)
+    }
+
+    pub fn due_date(&mut self, id: &TaskId, date: Option<NaiveDate>) -> Result<&mut Self, Error> {
+        self.project.tasks.set_due_date(id, date)?;
+        Ok(self)
+    }
+
+    pub fn done_date(&mut self, id: &TaskId, date: NaiveDate) -> Result<&mut Self, Error> {
+        self.project.tasks.set_done_date(id, date)?;
+        Ok(self)
+    }
+
+    pub fn earned_value_mode(&mut self, mode: EvMode) -> &mut Self {
+        self.project.tasks.set_earned_value_mode(mode);
+        self
+    }
+
+    pub fn recompute_aggregates(&mut self) -> &mut Self {
+        self.project.tasks.recompute_aggregates();
+        self
+    }
+
+    pub fn target(&mut self, target: Option<f64>) -> &mut Self {
+        self.project.tasks.set_target(target);
+        self
+    }
+
+    pub fn scale_values(&mut self, factor: f64) -> Result<&mut Self, Error> {
+        self.project.tasks.scale_values(factor)?;
+        Ok(self)
+    }
+
+    pub fn apply_analogous_estimate(&mut self, reference: &Tasks, scale: f64) -> Result<&mut Self, Error> {
+        self.project.tasks.apply_analogous_estimate(reference, scale)?;
+        Ok(self)
+    }
 }
@@ -50,13 +50,21 @@ impl Members {
             .ok_or_else(|| Error::MemberNotFound(name.to_string()))
     }
 
+    /// Follow tasks that changed ids
+    pub(crate) fn remap_task_ids(&mut self, mapping: &HashMap<TaskId, TaskId>) {
+        self.members
+            .values_mut()
+            .for_each(|member| member.remap_tasks(mapping));
+    }
+
     pub(crate) fn assign_task_to_member(&mut self, id: TaskId, name: &str, tasks: &mut Tasks) -> Result<(), Error> {
 
         if tasks.get(&id)?.is_trunk() {
             return Err(Error::TrunkCannotAddMember(id.clone()))
         }
 
-        self.get_mut(name)?.add_task(id);
+        self.get_mut(name)?.add_task(id.clone());
+        tasks.get_mut(&id)?.add_member(name);
         Ok(())
     }
 
@@ -67,7 +75,9 @@ impl Members {
         } else if !self.get(name)?.is_assigned_to(id) {
             return Err(Error::CannotRemoveMemberFromTask(id.clone(), name.to_string()))
         }
-        Ok(self.get_mut(name)?.remove_task(id))
+        self.get_mut(name)?.remove_task(id);
+        tasks.get_mut(id)?.remove_member(name);
+        Ok(())
     }
 
     pub(crate) fn remove_member(&mut self, name: &str, tasks: &mut Tasks) -> Result<Member, Error> {
@@ -30,6 +30,10 @@ impl Member {
         }
     }
 
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     pub fn is_assigned_to(&self, task_id: &TaskId) -> bool {
         self.tasks.contains(task_id)
     }
@@ -46,6 +50,13 @@ impl Member {
         self.tasks.remove(task_id);
     }
 
+    pub(crate) fn remap_tasks(&mut self, mapping: &HashMap<TaskId, TaskId>) {
+        self.tasks = self.tasks
+            .drain()
+            .map(|id| mapping.get(&id).cloned().unwrap_or(id))
+            .collect();
+    }
+
     fn to_datetime(date: &NaiveDate) -> NaiveDateTime {
         date.and_hms(0, 0, 0)
     }
@@ -4,8 +4,12 @@ pub use crate::{
     project::Project,
     task::{
         Task,
+        TaskStatus,
         task_id::TaskId,
-        tasks::Tasks
+        tasks::Tasks,
+        template::{Template, TemplateEntry},
+        export::{CsvOptions, CsvColumn, DotStyle, ExportFormat},
+        metrics::{EvmMetrics, EvMode, ConfidenceWeights},
     },
     member::{
         Member,
@@ -1,24 +1,48 @@
+use chrono::{NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use std::io::Write;
 
-use crate::{prelude::{Tasks, Members, Error}, interface::{task_execution::TaskExecution, member_execution::MemberExecution}, sprint::sprint::Sprints, util::DEFAULT_FILENAME};
+use crate::{prelude::{Tasks, Members, Error, Template, ExportFormat}, interface::{task_execution::TaskExecution, member_execution::MemberExecution}, sprint::sprint::Sprints, util::DEFAULT_FILENAME};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Project {
 
     pub(crate) tasks: Tasks,
     pub(crate) members: Members,
-    pub(crate) sprints: Sprints
+    pub(crate) sprints: Sprints,
+    /// when the project was started, in UTC. Unknown for files saved before it was kept
+    #[serde(default)]
+    created_at: Option<NaiveDateTime>,
 }
 
 impl Project {
 
     pub fn new(name: &str) -> Self {
+        Self::with_tasks(Tasks::new(name))
+    }
+
+    /// Same as `new`, but tasks are kept sorted by id, see `Tasks::new_ordered`
+    pub fn new_ordered(name: &str) -> Self {
+        Self::with_tasks(Tasks::new_ordered(name))
+    }
+
+    /// Same as `new`, but with room for `capacity` tasks preallocated
+    pub fn with_capacity(name: &str, capacity: usize) -> Self {
+        Self::with_tasks(Tasks::with_capacity(name, capacity))
+    }
+
+    /// Start a project from a template, named after it
+    pub fn from_template(template: &Template) -> Result<Self, Error> {
+        Ok(Self::with_tasks(Tasks::from_template(template)?))
+    }
+
+    fn with_tasks(tasks: Tasks) -> Self {
         Self {
-            tasks: Tasks::new(name),
+            tasks,
             members: Members::new(),
-            sprints: Sprints::new()
+            sprints: Sprints::new(),
+            created_at: Some(Utc::now().naive_utc()),
         }
     }
 
@@ -40,10 +64,33 @@ impl Project {
         Ok(self)
     }
 
+    /// Write the tasks in the given format
+    pub fn export<W: Write>(&self, format: ExportFormat, writer: &mut W) -> std::io::Result<()> {
+        let s = match format {
+            ExportFormat::Dot => self.tasks.to_dot_str(),
+            ExportFormat::Mermaid => self.tasks.to_mermaid_str(),
+            ExportFormat::Csv => self.tasks.to_csv(&self.members),
+            ExportFormat::Json => self.tasks.to_json().map_err(std::io::Error::other)?,
+            ExportFormat::Tree => self.tasks.to_tree_str(),
+            ExportFormat::Markdown => self.tasks.to_markdown_checklist(),
+        };
+        writer.write_all(s.as_bytes())
+    }
+
     pub fn name(&self) -> &str {
         self.tasks.name()
     }
 
+    pub fn created_at(&self) -> Option<NaiveDateTime> {
+        self.created_at
+    }
+
+    /// Budget at completion: the project's target when one is set (see `TaskExecution::target`),
+    /// otherwise the planned value rolled up from the tasks
+    pub fn budget_at_completion(&self) -> f64 {
+        self.tasks.budget_at_completion()
+    }
+
     pub fn tasks(&self) -> &Tasks {
         &self.tasks
     }
@@ -71,8 +118,11 @@ impl Project {
     }
 
     fn from_json(project_str: &str) -> Result<Self, Error> {
-        serde_json::from_str(project_str)
-            .or_else(|_| Err(Error::ParseJsonContents(project_str.to_string())))
+        let mut project: Self = serde_json::from_str(project_str)
+            .map_err(|_| Error::ParseJsonContents(project_str.to_string()))?;
+        project.tasks.restore();
+        project.tasks.sync_members(&project.members);
+        Ok(project)
     }
 
     fn to_json(&self) -> Result<String, Error> {
@@ -83,4 +133,105 @@ impl Project {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::prelude::TaskId;
+
+    #[test]
+    fn export() {
+        let mut project = Project::new("Project");
+        project.tasks_mut(|tasks| {
+            tasks
+                .expand(&[("", "Design"), ("1", "Schema"), ("", "Build")])?
+                .add_dependency(&TaskId::new(vec![2]), &TaskId::new(vec![1, 1]))?
+                .done(&TaskId::new(vec![1, 1]), 2.0)?;
+            Ok(())
+        }).unwrap();
+
+        let tasks = project.tasks();
+        [
+            (ExportFormat::Dot, tasks.to_dot_str()),
+            (ExportFormat::Mermaid, tasks.to_mermaid_str()),
+            (ExportFormat::Csv, tasks.to_csv(project.members())),
+            (ExportFormat::Json, tasks.to_json().unwrap()),
+            (ExportFormat::Tree, tasks.to_tree_str()),
+            (ExportFormat::Markdown, tasks.to_markdown_checklist()),
+        ].into_iter().for_each(|(format, expected)| {
+            let mut bytes = Vec::new();
+            project.export(format, &mut bytes).unwrap();
+            assert_eq!(bytes, expected.into_bytes(), "{:?}", format);
+        });
+    }
+
+    #[test]
+    fn task_members_on_load() {
+        let mut project = Project::new("Project");
+        project.tasks_mut(|tasks| {
+            tasks.expand(&[("", "Design"), ("", "Build")])?;
+            Ok(())
+        }).unwrap();
+        project.members_mut(|members| {
+            members
+                .add_member("alice")?
+                .assign_task_to_member(TaskId::new(vec![2]), "alice")?;
+            Ok(())
+        }).unwrap();
+        assert!(project.tasks().get(&TaskId::new(vec![2])).unwrap().has_member("alice"));
+
+        // files saved before tasks kept their members get them back from the members' side
+        project.tasks.get_mut(&TaskId::new(vec![2])).unwrap().members.clear();
+        let loaded = Project::from_json(&project.to_json().unwrap()).unwrap();
+        let build = loaded.tasks().get(&TaskId::new(vec![2])).unwrap();
+        assert_eq!(build.members().collect::<Vec<&str>>(), vec!["alice"]);
+        assert!(!loaded.tasks().get(&TaskId::new(vec![1])).unwrap().has_member("alice"));
+    }
+
+    #[test]
+    fn created_at_and_budget() {
+        let before = Utc::now().naive_utc();
+        let mut project = Project::new("Project");
+        let created_at = project.created_at().unwrap();
+        assert!(before <= created_at && created_at <= Utc::now().naive_utc());
+
+        project.tasks_mut(|tasks| {
+            tasks
+                .expand(&[("", "Design"), ("", "Build")])?
+                .planned_value(&TaskId::new(vec![1]), 3.0)?;
+            Ok(())
+        }).unwrap();
+        assert_eq!(project.budget_at_completion(), 3.0);
+        project.tasks_mut(|tasks| {
+            tasks.target(Some(10.0));
+            Ok(())
+        }).unwrap();
+        assert_eq!(project.budget_at_completion(), 10.0);
+
+        let loaded = Project::from_json(&project.to_json().unwrap()).unwrap();
+        assert_eq!(loaded.created_at(), Some(created_at));
+        assert_eq!(loaded.budget_at_completion(), 10.0);
+
+        // project files from before creation times still load
+        project.created_at = None;
+        let json = project.to_json().unwrap().replace(",\"created_at\":null", "");
+        assert!(!json.contains("created_at"));
+        assert_eq!(Project::from_json(&json).unwrap().created_at(), None);
+    }
+
+    #[test]
+    fn ordered_store_on_load() {
+        let mut project = Project::new_ordered("Project");
+        project.tasks_mut(|tasks| {
+            tasks.expand(&[("", "Design"), ("1", "Schema"), ("", "Build")])?;
+            Ok(())
+        }).unwrap();
+
+        let loaded = Project::from_json(&project.to_json().unwrap()).unwrap();
+        assert!(loaded.tasks().is_ordered());
+        assert_eq!(loaded.tasks().leaf_count(), 2);
+        assert!(!Project::from_json(&Project::new("Project").to_json().unwrap()).unwrap().tasks().is_ordered());
+
+        // files saved before the kind was kept load as hashed
+        let json = project.to_json().unwrap().replace(",\"ordered\":true", "");
+        assert!(!json.contains("ordered"));
+        assert!(!Project::from_json(&json).unwrap().tasks().is_ordered());
+    }
 }
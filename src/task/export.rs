@@ -0,0 +1,789 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::prelude::{Tasks, Task, Members, TaskId, TaskStatus, Error};
+use super::tasks::escape_dot_label;
+
+/// Output format of `Project::export`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// `Tasks::to_dot_str`
+    Dot,
+    /// `Tasks::to_mermaid_str`
+    Mermaid,
+    /// `Tasks::to_csv`
+    Csv,
+    /// `Tasks::to_json`
+    Json,
+    /// `Tasks::to_tree_str`
+    Tree,
+    /// `Tasks::to_markdown_checklist`
+    Markdown,
+}
+
+/// Column of a CSV export
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CsvColumn {
+    Id,
+    Name,
+    Status,
+    PlannedValue,
+    ActualCost,
+    Completion,
+    Members,
+}
+
+impl CsvColumn {
+    fn header(&self) -> &'static str {
+        match self {
+            CsvColumn::Id => "id",
+            CsvColumn::Name => "name",
+            CsvColumn::Status => "status",
+            CsvColumn::PlannedValue => "planned_value",
+            CsvColumn::ActualCost => "actual_cost",
+            CsvColumn::Completion => "completion",
+            CsvColumn::Members => "members",
+        }
+    }
+}
+
+/// Options for `Tasks::to_csv_with`. The default exports leaf tasks only, with the id, name,
+/// status, planned value and actual cost columns, separated by commas
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// Also emit a row for each trunk task (including the root), with its rolled-up values
+    pub include_trunks: bool,
+    pub columns: Vec<CsvColumn>,
+    pub delimiter: char,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            include_trunks: false,
+            columns: vec![
+                CsvColumn::Id,
+                CsvColumn::Name,
+                CsvColumn::Status,
+                CsvColumn::PlannedValue,
+                CsvColumn::ActualCost,
+            ],
+            delimiter: ',',
+        }
+    }
+}
+
+/// Options for `Tasks::to_dot_str_with`. The default matches `Tasks::to_dot_str`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DotStyle {
+    /// Render each task as an HTML-like table, one row per field, instead of a plain text label
+    pub html_labels: bool,
+    /// Draw the edges into fully done subtrees bold and green, and the ones into subtrees with
+    /// nothing done yet gray
+    pub progress_edges: bool,
+}
+
+fn html_escape(s: &str) -> String {
+    s.chars().fold(String::with_capacity(s.len()), |mut acc, c| {
+        match c {
+            '&' => acc.push_str("&amp;"),
+            '<' => acc.push_str("&lt;"),
+            '>' => acc.push_str("&gt;"),
+            '"' => acc.push_str("&quot;"),
+            '\'' => acc.push_str("&#39;"),
+            c => acc.push(c),
+        }
+        acc
+    })
+}
+
+fn dot_node_id(task_id: &TaskId) -> String {
+    if task_id.as_vec().is_empty() {
+        "root".to_string()
+    } else {
+        task_id.to_string()
+    }
+}
+
+fn mermaid_node_id(task_id: &TaskId) -> String {
+    dot_node_id(task_id).replace('.', "_")
+}
+
+fn html_label(task: &Task) -> String {
+    let title = if task.id().as_vec().is_empty() {
+        html_escape(task.name())
+    } else {
+        format!("{} - {}", task.id(), html_escape(task.name()))
+    };
+    format!(
+        concat!(
+            "<table border=\"0\" cellborder=\"1\" cellspacing=\"0\">",
+            "<tr><td colspan=\"2\"><b>{}</b></td></tr>",
+            "<tr><td>status</td><td><font color=\"{}\">{}</font></td></tr>",
+            "<tr><td>pv</td><td>{}</td></tr>",
+            "<tr><td>ac</td><td>{}</td></tr>",
+            "</table>"),
+        title,
+        task.status.to_color(),
+        task.status.to_icon(),
+        task.get_planned_value(),
+        task.get_actual_cost())
+}
+
+fn csv_field(field: &str, delimiter: char) -> String {
+    if field.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn csv_row(fields: impl Iterator<Item=String>, delimiter: char) -> String {
+    fields
+        .map(|field| csv_field(&field, delimiter))
+        .collect::<Vec<String>>()
+        .join(&delimiter.to_string())
+}
+
+/// Records of a comma separated CSV, each with the line it starts at (counting from 1), or the
+/// line of an unterminated quoted field
+fn csv_records(s: &str) -> Result<Vec<(usize, Vec<String>)>, Error> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let (mut line, mut record_line) = (1, 1);
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            },
+            '"' if in_quotes || field.is_empty() => in_quotes = !in_quotes,
+            '\n' if in_quotes => {
+                line += 1;
+                field.push(c);
+            },
+            ',' if !in_quotes => record.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {},
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push((record_line, std::mem::take(&mut record)));
+                line += 1;
+                record_line = line;
+            },
+            c => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(Error::BadCsvRow(record_line));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push((record_line, record));
+    }
+    Ok(records)
+}
+
+/// Position of each of `names` in the header, the first of `records`. A missing one is a
+/// `BadCsvRow` on the header's line
+fn csv_columns<const N: usize>(records: &[(usize, Vec<String>)], names: [&str; N]) -> Result<[usize; N], Error> {
+    let (header_line, header) = records.first().ok_or(Error::BadCsvRow(1))?;
+    let mut columns = [0; N];
+    for (column, name) in columns.iter_mut().zip(names) {
+        *column = header
+            .iter()
+            .position(|field| field == name)
+            .ok_or(Error::BadCsvRow(*header_line))?;
+    }
+    Ok(columns)
+}
+
+/// Align columns of a table, the first `text_columns` ones to the left and the rest to the right
+fn format_table(rows: &[Vec<String>], text_columns: usize) -> String {
+    let widths = (0..rows[0].len())
+        .map(|column| rows.iter().map(|row| row[column].chars().count()).max().unwrap_or(0))
+        .collect::<Vec<usize>>();
+    rows
+        .iter()
+        .map(|row| {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(column, (field, width))| if column < text_columns {
+                    format!("{:<width$}", field, width = width)
+                } else {
+                    format!("{:>width$}", field, width = width)
+                })
+                .collect::<Vec<String>>()
+                .join("  ");
+            line.trim_end().to_string() + "\n"
+        })
+        .collect()
+}
+
+impl Tasks {
+
+    /// Leaf tasks as CSV, in pre-order, with the default `CsvOptions`
+    pub fn to_csv(&self, members: &Members) -> String {
+        self.to_csv_with(members, &CsvOptions::default())
+    }
+
+    /// Tasks as CSV, in pre-order, with a header row naming the chosen columns
+    pub fn to_csv_with(&self, members: &Members, options: &CsvOptions) -> String {
+        let header = csv_row(options.columns.iter().map(|column| column.header().to_string()), options.delimiter);
+        self.dfs_ids()
+            .iter()
+            .map(|id| self.get(id).unwrap())
+            .filter(|task| options.include_trunks || Tasks::is_leaf_task(task))
+            .fold(header + "\n", |acc, task| {
+                let fields = options.columns.iter().map(|column| match column {
+                    CsvColumn::Id => task.id().to_string(),
+                    CsvColumn::Name => task.name().to_string(),
+                    CsvColumn::Status => task.status.to_string(),
+                    CsvColumn::PlannedValue => task.get_planned_value().to_string(),
+                    CsvColumn::ActualCost => task.get_actual_cost().to_string(),
+                    CsvColumn::Completion => self.subtree_completion(task.id()).unwrap().to_string(),
+                    CsvColumn::Members => Self::member_names(task.id(), members),
+                });
+                acc + &csv_row(fields, options.delimiter) + "\n"
+            })
+    }
+
+    /// Rebuild tasks from comma separated CSV with at least the `id`, `name`, `status`,
+    /// `planned_value` and `actual_cost` columns, like `to_csv` writes. Every task comes right after
+    /// its previous sibling's subtree (or its parent). The root and trunk rows, which `to_csv_with`
+    /// only writes when including trunks, are optional: missing ones are created named after their
+    /// ids. Values of trunk rows are ignored, since they are rolled up from the leaves
+    pub fn from_csv(s: &str) -> Result<Tasks, Error> {
+        let records = csv_records(s)?;
+        let [id_col, name_col, status_col, planned_value_col, actual_cost_col] =
+            csv_columns(&records, ["id", "name", "status", "planned_value", "actual_cost"])?;
+        let (header_line, header) = &records[0];
+
+        let mut rows = records[1..].iter().filter(|(_, fields)| fields.iter().any(|field| !field.is_empty())).peekable();
+        let mut tasks = match rows.peek() {
+            Some((_, fields)) if fields.len() == header.len() && fields[id_col].is_empty() => {
+                let tasks = Tasks::new(&fields[name_col]);
+                rows.next();
+                tasks
+            },
+            Some(_) => Tasks::new(""),
+            None => return Err(Error::BadCsvRow(header_line + 1)),
+        };
+        let mut values = Vec::new();
+        for (line, fields) in rows {
+            let bad_row = || Error::BadCsvRow(*line);
+            if fields.len() != header.len() {
+                return Err(bad_row());
+            }
+            let id = TaskId::parse(&fields[id_col]).map_err(|_| bad_row())?;
+            tasks.fill_up_to(&id.parent().map_err(|_| bad_row())?).map_err(|_| bad_row())?;
+            if !tasks.is_next_child(&id) {
+                return Err(bad_row());
+            }
+            let status = match fields[status_col].as_str() {
+                "Done" => TaskStatus::Done,
+                "InProgress" | "Blocked" => TaskStatus::InProgress,
+                _ => return Err(bad_row()),
+            };
+            let planned_value = fields[planned_value_col].parse::<f64>().map_err(|_| bad_row())?;
+            let actual_cost = fields[actual_cost_col].parse::<f64>().map_err(|_| bad_row())?;
+            tasks.add_task(id.parent()?, &fields[name_col])?;
+            values.push((id, status, planned_value, actual_cost));
+        }
+
+        let mut statuses = HashMap::new();
+        for (id, status, planned_value, actual_cost) in values {
+            if tasks.get(&id)?.is_trunk() {
+                continue;
+            }
+            tasks.set_planned_value(&id, planned_value)?;
+            tasks.set_actual_cost(&id, actual_cost)?;
+            statuses.insert(id, status);
+        }
+        tasks.apply_status_updates(&statuses)?;
+        Ok(tasks)
+    }
+
+    /// Bootstrap a plan from comma separated CSV with `id`, `name` and `planned_value` columns, in
+    /// any order. Rows are added parent before child, and missing ancestors of a listed task are
+    /// created named after their ids, but a task can't come without its earlier siblings. A root
+    /// row is ignored, the project being named `name`, and so are the values of rows that end up
+    /// as trunks
+    pub fn from_planned_values_csv(name: &str, s: &str) -> Result<Tasks, Error> {
+        let records = csv_records(s)?;
+        let [id_col, name_col, planned_value_col] = csv_columns(&records, ["id", "name", "planned_value"])?;
+        let header = &records[0].1;
+
+        let mut rows = BTreeMap::new();
+        for (line, fields) in records[1..].iter().filter(|(_, fields)| fields.iter().any(|field| !field.is_empty())) {
+            let bad_row = || Error::BadCsvRow(*line);
+            if fields.len() != header.len() {
+                return Err(bad_row());
+            }
+            let id = TaskId::parse(&fields[id_col])?;
+            let planned_value = fields[planned_value_col].parse::<f64>().map_err(|_| bad_row())?;
+            if rows.insert(id, (*line, fields[name_col].as_str(), planned_value)).is_some() {
+                return Err(bad_row());
+            }
+        }
+
+        let mut tasks = Tasks::new(name);
+        for (id, (line, task_name, _)) in rows.iter().filter(|(id, _)| !id.as_vec().is_empty()) {
+            let parent_id = id.parent()?;
+            tasks.fill_up_to(&parent_id).map_err(|_| Error::BadCsvRow(*line))?;
+            if !tasks.is_next_child(id) {
+                return Err(Error::BadCsvRow(*line));
+            }
+            tasks.add_task(parent_id, task_name)?;
+        }
+        for (id, (_, _, planned_value)) in rows.iter() {
+            if tasks.get(id)?.is_leaf() && !id.as_vec().is_empty() {
+                tasks.set_planned_value(id, *planned_value)?;
+            }
+        }
+        Ok(tasks)
+    }
+
+    /// Whether adding a child to the task's parent would give it this id
+    fn is_next_child(&self, task_id: &TaskId) -> bool {
+        task_id.parent().is_ok_and(|parent_id| self.get(&parent_id).is_ok_and(|parent| {
+            task_id.child_idx().is_ok_and(|idx| idx == parent.num_child + 1)
+        }))
+    }
+
+    /// Create a task, along with its missing ancestors, each named after its id. Only next
+    /// children are created, so a task whose earlier siblings are missing is `TaskNotFound`
+    fn fill_up_to(&mut self, task_id: &TaskId) -> Result<(), Error> {
+        if self.get(task_id).is_ok() {
+            return Ok(());
+        }
+        let parent_id = task_id.parent()?;
+        self.fill_up_to(&parent_id)?;
+        if !self.is_next_child(task_id) {
+            return Err(Error::TaskNotFound(task_id.clone()));
+        }
+        self.add_task(parent_id, &task_id.to_string())?;
+        Ok(())
+    }
+
+    /// Same as `to_dot_str`, but in the given style. With HTML-like labels nodes are named after
+    /// their ids, the root being `root`
+    pub fn to_dot_str_with(&self, style: &DotStyle) -> String {
+        if !style.html_labels {
+            return self.plain_dot_str(style.progress_edges);
+        }
+        let ids = self.dfs_ids();
+        let mut s = String::new();
+        ids.iter().map(|id| self.get(id).unwrap()).for_each(|task| {
+            let tooltip = task.description()
+                .map(|description| format!(", tooltip=\"{}\"", escape_dot_label(description)))
+                .unwrap_or_default();
+            s += &format!("\t\"{}\" [label=<{}>{}];\n", dot_node_id(task.id()), html_label(task), tooltip);
+        });
+        ids.iter().map(|id| self.get(id).unwrap()).for_each(|task| {
+            task.child_ids().for_each(|child_id| {
+                s += &format!("\t\"{}\" -> \"{}\" [{}]\n", dot_node_id(task.id()), dot_node_id(&child_id), self.tree_edge_attributes(&child_id, style.progress_edges));
+            });
+            let mut dependencies = task.dependencies.iter().collect::<Vec<&TaskId>>();
+            dependencies.sort();
+            dependencies.iter().for_each(|dependency_id| {
+                s += &format!("\t\"{}\" -> \"{}\" [style=dashed, weight=0, constraint=false]\n", dot_node_id(task.id()), dot_node_id(dependency_id));
+            });
+        });
+        format!("digraph G {{\n{}\nnode [shape=plain];\n{}}}", self.dot_graph_attributes(), s)
+    }
+
+    /// Attributes of the edge from a task to one of its children
+    pub(crate) fn tree_edge_attributes(&self, child_id: &TaskId, progress_edges: bool) -> &'static str {
+        if !progress_edges {
+            return "style=solid, weight=100, constraint=true";
+        }
+        match self.subtree_completion(child_id) {
+            Ok(completion) if completion >= 1.0 => "style=bold, weight=100, constraint=true, color=darkgreen",
+            Ok(completion) if completion <= 0.0 => "style=solid, weight=100, constraint=true, color=gray",
+            _ => "style=solid, weight=100, constraint=true",
+        }
+    }
+
+    /// Mermaid flowchart of the tree, with dependencies as dotted edges. Nodes are named after their
+    /// ids with dots replaced by underscores, the root being `root`
+    pub fn to_mermaid_str(&self) -> String {
+        let ids = self.dfs_ids();
+        let mut s = String::from("graph TD\n");
+        ids.iter().map(|id| self.get(id).unwrap()).for_each(|task| {
+            let label = if task.id().as_vec().is_empty() {
+                format!("{} {}", task.name(), task.status.to_icon())
+            } else {
+                format!("{} - {} {}", task.id(), task.name(), task.status.to_icon())
+            };
+            s += &format!("    {}[\"{}\"]\n", mermaid_node_id(task.id()), label.replace('"', "#quot;"));
+        });
+        ids.iter().map(|id| self.get(id).unwrap()).for_each(|task| {
+            task.child_ids().for_each(|child_id| {
+                s += &format!("    {} --> {}\n", mermaid_node_id(task.id()), mermaid_node_id(&child_id));
+            });
+        });
+        ids.iter().map(|id| self.get(id).unwrap()).for_each(|task| {
+            let mut dependencies = task.dependencies.iter().collect::<Vec<&TaskId>>();
+            dependencies.sort();
+            dependencies.iter().for_each(|dependency_id| {
+                s += &format!("    {} -.-> {}\n", mermaid_node_id(task.id()), mermaid_node_id(dependency_id));
+            });
+        });
+        s
+    }
+
+    /// Markdown checklist of the tasks in pre-order, under a heading with the project name. Each
+    /// item is nested two spaces under its parent, so trunks show up as parent items, and ticked
+    /// when the task is done
+    pub fn to_markdown_checklist(&self) -> String {
+        let mut s = format!("# {}\n\n", self.name());
+        self.descendants(&TaskId::get_root_id())
+            .for_each(|task| {
+                let check = if task.status == TaskStatus::Done { 'x' } else { ' ' };
+                let indent = "  ".repeat(task.id().len() - 1);
+                s += &format!("{}- [{}] {} {}\n", indent, check, task.id(), task.name());
+            });
+        s
+    }
+
+    fn member_names(task_id: &TaskId, members: &Members) -> String {
+        let mut names = members
+            .members()
+            .filter(|member| member.is_assigned_to(task_id))
+            .map(|member| member.name())
+            .collect::<Vec<&str>>();
+        names.sort_unstable();
+        names.join(" ")
+    }
+
+    /// Earned value management line items: baseline planned value, current planned value, earned
+    /// value, actual cost, schedule variance (against the baseline) and cost variance of each leaf
+    /// task, in pre-order. There are no baselines yet, so the current planned value is used as one
+    pub fn variance_table(&self) -> String {
+        let mut rows = vec![
+            ["id", "name", "baseline", "pv", "ev", "ac", "sv", "cv"]
+                .iter()
+                .map(|column| column.to_string())
+                .collect::<Vec<String>>()
+        ];
+        rows.extend(self.subtree_leaves(&TaskId::get_root_id())
+            .map(|task| {
+                let baseline = task.get_planned_value();
+                let earned_value = task.get_earned_value();
+                [
+                    baseline,
+                    task.get_planned_value(),
+                    earned_value,
+                    task.get_actual_cost(),
+                    earned_value - baseline,
+                    earned_value - task.get_actual_cost(),
+                ]
+                    .iter()
+                    .fold(vec![task.id().to_string(), task.name().to_string()], |mut row, value| {
+                        row.push(format!("{:.2}", value));
+                        row
+                    })
+            }));
+        format_table(&rows, 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_with_trunks() {
+        let mut tasks = Tasks::new("Project");
+        let mut members = Members::new();
+        tasks.expand(&[
+            ("", "Design; review"),
+                ("1", "Schema"),
+                ("1", "API"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 1]), 2.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 2]), 3.5).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 1.0).unwrap();
+        members.insert("alice".to_string()).unwrap();
+        members.assign_task_to_member(TaskId::new(vec![1, 2]), "alice", &mut tasks).unwrap();
+
+        let options = CsvOptions {
+            include_trunks: true,
+            columns: vec![CsvColumn::Id, CsvColumn::Name, CsvColumn::PlannedValue, CsvColumn::Completion, CsvColumn::Members],
+            delimiter: ';',
+        };
+        assert_eq!(tasks.to_csv_with(&members, &options), concat!(
+            "id;name;planned_value;completion;members\n",
+            ";Project;5.5;0.5;\n",
+            "1;\"Design; review\";5.5;0.5;\n",
+            "1.1;Schema;2;1;\n",
+            "1.2;API;3.5;0;alice\n",
+        ));
+        assert_eq!(tasks.to_csv(&members), concat!(
+            "id,name,status,planned_value,actual_cost\n",
+            "1.1,Schema,Done,2,1\n",
+            "1.2,API,InProgress,3.5,0\n",
+        ));
+    }
+
+    #[test]
+    fn csv_quoting() {
+        let mut tasks = Tasks::new("Project");
+        let members = Members::new();
+        tasks.expand(&[
+            ("", "Design, review"),
+            ("", "The \"big\" build"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1]), 2.0).unwrap();
+
+        let csv = tasks.to_csv(&members);
+        assert_eq!(csv, concat!(
+            "id,name,status,planned_value,actual_cost\n",
+            "1,\"Design, review\",InProgress,2,0\n",
+            "2,\"The \"\"big\"\" build\",InProgress,0,0\n",
+        ));
+        let records = csv_records(&csv).unwrap();
+        assert_eq!(records[1].1, ["1", "Design, review", "InProgress", "2", "0"]);
+        assert_eq!(records[2].1[1], "The \"big\" build");
+    }
+
+    #[test]
+    fn variance_table() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+            ("", "Build"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 1]), 4.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2]), 12.5).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 5.0).unwrap();
+
+        assert_eq!(tasks.variance_table(), concat!(
+            "id   name    baseline     pv    ev    ac      sv     cv\n",
+            "1.1  Schema      4.00   4.00  4.00  5.00    0.00  -1.00\n",
+            "2    Build      12.50  12.50  0.00  0.00  -12.50   0.00\n",
+        ));
+    }
+
+    #[test]
+    fn dot_str_html_labels() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design <v2> & review"),
+            ("", "Build"),
+        ]).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![2]), &TaskId::new(vec![1])).unwrap();
+
+        assert_eq!(tasks.to_dot_str_with(&DotStyle::default()), tasks.to_dot_str());
+        let dot = tasks.to_dot_str_with(&DotStyle { html_labels: true, ..DotStyle::default() });
+        assert!(dot.contains("\t\"root\" [label=<<table border=\"0\" cellborder=\"1\" cellspacing=\"0\"><tr><td colspan=\"2\"><b>Project</b></td></tr>"));
+        assert!(dot.contains("<b>1 - Design &lt;v2&gt; &amp; review</b>"));
+        assert!(dot.contains("<tr><td>status</td><td><font color=\"darkorange\">⊘</font></td></tr>"));
+        assert!(dot.contains("\t\"root\" -> \"1\" [style=solid, weight=100, constraint=true]\n"));
+        assert!(dot.contains("\t\"2\" -> \"1\" [style=dashed, weight=0, constraint=false]\n"));
+        assert!(!dot.contains("tooltip"));
+
+        tasks.set_description(&TaskId::new(vec![2]), Some("Ship it")).unwrap();
+        let dot = tasks.to_dot_str_with(&DotStyle { html_labels: true, ..DotStyle::default() });
+        assert!(dot.contains("</table>>, tooltip=\"Ship it\"];\n"));
+        assert_eq!(dot.matches("tooltip").count(), 1);
+    }
+
+    #[test]
+    fn mermaid_str() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design \"v2\""),
+                ("1", "Schema"),
+            ("", "Build"),
+        ]).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![2]), &TaskId::new(vec![1, 1])).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 1.0).unwrap();
+
+        assert_eq!(tasks.to_mermaid_str(), concat!(
+            "graph TD\n",
+            "    root[\"Project ✗\"]\n",
+            "    1[\"1 - Design #quot;v2#quot; ✔\"]\n",
+            "    1_1[\"1.1 - Schema ✔\"]\n",
+            "    2[\"2 - Build ✗\"]\n",
+            "    root --> 1\n",
+            "    root --> 2\n",
+            "    1 --> 1_1\n",
+            "    2 -.-> 1_1\n",
+        ));
+    }
+
+    #[test]
+    fn markdown_checklist() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+                    ("1.2", "Routes"),
+            ("", "Build"),
+        ]).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 1.0).unwrap();
+
+        assert_eq!(tasks.to_markdown_checklist(), concat!(
+            "# Project\n",
+            "\n",
+            "- [ ] 1 Design\n",
+            "  - [x] 1.1 Schema\n",
+            "  - [ ] 1.2 API\n",
+            "    - [ ] 1.2.1 Routes\n",
+            "- [ ] 2 Build\n",
+        ));
+
+        // trunks are ticked once all their leaves are done
+        tasks.set_actual_cost(&TaskId::new(vec![1, 2, 1]), 1.0).unwrap();
+        assert!(tasks.to_markdown_checklist().contains("- [x] 1 Design\n  - [x] 1.1 Schema\n  - [x] 1.2 API\n"));
+    }
+
+    #[test]
+    fn from_csv() {
+        let mut tasks = Tasks::new("Project");
+        let members = Members::new();
+        tasks.expand(&[
+            ("", "Design, \"v2\""),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build\nand ship"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 1]), 2.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 2]), 3.5).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2]), 1.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 1.0).unwrap();
+        let options = CsvOptions { include_trunks: true, ..CsvOptions::default() };
+
+        let csv = tasks.to_csv_with(&members, &options);
+        let imported = Tasks::from_csv(&csv).unwrap();
+        assert_eq!(imported.to_csv_with(&members, &options), csv);
+        assert_eq!(imported.to_tree_str(), tasks.to_tree_str());
+
+        assert_eq!(Tasks::from_csv(concat!(
+            "id,name,status,planned_value,actual_cost\n",
+            ",Project,InProgress,0,0\n",
+            "1,Design,InProgress,0,0\n",
+            "1.2,API,InProgress,0,0\n",
+        )).unwrap_err(), Error::BadCsvRow(4));
+        assert_eq!(Tasks::from_csv(concat!(
+            "id,name,status,planned_value,actual_cost\n",
+            ",Project,InProgress,0,0\n",
+            "1,\"Design\nphase\",InProgress,0,0\n",
+            "2,Build,InProgress,lots,0\n",
+        )).unwrap_err(), Error::BadCsvRow(5));
+        assert_eq!(Tasks::from_csv(concat!(
+            "id,name,status,planned_value,actual_cost\n",
+            ",Project,InProgress,0,0\n",
+            "1.1,Schema,InProgress,0,0\n",
+            "1,Design,InProgress,0,0\n",
+        )).unwrap_err(), Error::BadCsvRow(4));
+        assert_eq!(Tasks::from_csv(concat!(
+            "id,name,status,planned_value,actual_cost\n",
+            "1,Design,InProgress,0,0\n",
+            ",Project,InProgress,0,0\n",
+        )).unwrap_err(), Error::BadCsvRow(3));
+        assert_eq!(Tasks::from_csv("id,name\n,Project\n").unwrap_err(), Error::BadCsvRow(1));
+    }
+
+    #[test]
+    fn from_csv_leaves_only() {
+        let mut tasks = Tasks::new("Project");
+        let members = Members::new();
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                    ("1.1", "Tables"),
+                ("1", "API"),
+            ("", "Build"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 1, 1]), 2.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 2]), 3.5).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1, 1]), 1.0).unwrap();
+
+        let csv = tasks.to_csv(&members);
+        let imported = Tasks::from_csv(&csv).unwrap();
+        assert_eq!(imported.to_csv(&members), csv);
+        assert_eq!(imported.planned_value(), tasks.planned_value());
+        assert_eq!(imported.actual_cost(), tasks.actual_cost());
+        // the root and trunks weren't exported, so they are named after their ids
+        assert_eq!(
+            imported.dfs_ids().iter().map(|id| imported.get(id).unwrap().name()).collect::<Vec<&str>>(),
+            ["", "1", "1.1", "Tables", "API", "Build"]);
+    }
+
+    #[test]
+    fn dot_str_progress_edges() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+            ("", "Build"),
+            ("", "Docs"),
+                ("3", "Guide"),
+                ("3", "Reference"),
+        ]).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 1.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![3, 1]), 1.0).unwrap();
+
+        let style = DotStyle { progress_edges: true, ..DotStyle::default() };
+        let dot = tasks.to_dot_str_with(&DotStyle { html_labels: true, ..style });
+        assert!(dot.contains("\t\"root\" -> \"1\" [style=bold, weight=100, constraint=true, color=darkgreen]\n"));
+        assert!(dot.contains("\t\"1\" -> \"1.1\" [style=bold, weight=100, constraint=true, color=darkgreen]\n"));
+        assert!(dot.contains("\t\"root\" -> \"2\" [style=solid, weight=100, constraint=true, color=gray]\n"));
+        assert!(dot.contains("\t\"root\" -> \"3\" [style=solid, weight=100, constraint=true]\n"));
+
+        let dot = tasks.to_dot_str_with(&style);
+        assert_eq!(dot.matches("color=darkgreen]").count(), 3);
+        assert_eq!(dot.matches("color=gray]").count(), 2);
+        assert!(!tasks.to_dot_str().contains("color="));
+    }
+
+    #[test]
+    fn from_planned_values_csv() {
+        let tasks = Tasks::from_planned_values_csv("Project", concat!(
+            "name,id,planned_value\n",
+            "API,1.2,3.5\n",
+            "\"Design, v2\",1,10\n",
+            "Schema,1.1,2\n",
+            "Routes,2.1.1,1\n",
+        )).unwrap();
+        assert_eq!(tasks.name(), "Project");
+        assert_eq!(
+            tasks.dfs_ids().iter().map(|id| (id.to_string(), tasks.get(id).unwrap().name())).collect::<Vec<(String, &str)>>(),
+            [("", "Project"), ("1", "Design, v2"), ("1.1", "Schema"), ("1.2", "API"), ("2", "2"), ("2.1", "2.1"), ("2.1.1", "Routes")]
+                .map(|(id, name)| (id.to_string(), name)));
+        // "Design" became a trunk, so its own value is dropped
+        assert_eq!(tasks.planned_value(), 6.5);
+        tasks.debug_assert_rollups();
+
+        assert_eq!(
+            Tasks::from_planned_values_csv("Project", "id,name,planned_value\n1,Design,1\n1.x,Schema,2\n").unwrap_err(),
+            Error::BadTaskIdString("1.x".to_string()));
+        assert_eq!(
+            Tasks::from_planned_values_csv("Project", "id,name,planned_value\n1,Design,1\n1.0,Schema,2\n").unwrap_err(),
+            Error::BadTaskIdString("1.0".to_string()));
+        assert_eq!(
+            Tasks::from_planned_values_csv("Project", "id,name,planned_value\n1,Design,1\n2,Build,lots\n").unwrap_err(),
+            Error::BadCsvRow(3));
+        assert_eq!(
+            Tasks::from_planned_values_csv("Project", "id,name,planned_value\n1,Design,1\n1,Build,2\n").unwrap_err(),
+            Error::BadCsvRow(3));
+        assert_eq!(
+            Tasks::from_planned_values_csv("Project", "id,name\n1,Design\n").unwrap_err(),
+            Error::BadCsvRow(1));
+        // siblings can't be skipped, directly or on the way to a deeper task
+        assert_eq!(
+            Tasks::from_planned_values_csv("Project", "id,name,planned_value\n1,Design,1\n3,Docs,2\n").unwrap_err(),
+            Error::BadCsvRow(3));
+        assert_eq!(
+            Tasks::from_planned_values_csv("Project", "id,name,planned_value\n1,Design,1\n3.1,Guide,2\n").unwrap_err(),
+            Error::BadCsvRow(3));
+    }
+}
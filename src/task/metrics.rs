@@ -0,0 +1,375 @@
+use chrono::NaiveDate;
+use serde::{Serialize, Deserialize};
+
+use crate::prelude::{Task, Tasks, TaskId, TaskStatus, Error};
+
+/// How the earned value of trunk tasks is computed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EvMode {
+    /// A trunk has earned the planned value of its done leaves, so value is earned gradually as
+    /// leaves get done
+    #[default]
+    LeafSum,
+    /// A trunk is a milestone: its whole planned value is earned once it's done, and nothing
+    /// before that
+    MilestoneOnTrunk,
+}
+
+/// Earned value management figures of a project
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct EvmMetrics {
+    pub planned_value: f64,
+    pub actual_cost: f64,
+    pub earned_value: f64,
+    pub spi: f64,
+    pub sv: f64,
+    pub cpi: f64,
+    pub cv: f64,
+    /// estimate at completion
+    pub eac: f64,
+    /// variance at completion
+    pub vac: f64,
+    /// to-complete performance index
+    pub tcpi: f64,
+}
+
+/// `numerator / denominator`, or 0.0 when that isn't a finite number (a zero denominator, mostly)
+pub(crate) fn safe_ratio(numerator: f64, denominator: f64) -> f64 {
+    let res = numerator / denominator;
+    if res.is_finite() {
+        res
+    } else {
+        0.0
+    }
+}
+
+/// Weights of the signals in `Tasks::completion_confidence`. Only their proportions matter
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConfidenceWeights {
+    /// schedule performance, `spi` capped at 1
+    pub schedule: f64,
+    /// cost performance, `cpi` capped at 1
+    pub cost: f64,
+    /// fraction of the open leaf tasks that are neither blocked nor unassigned
+    pub flow: f64,
+}
+
+impl Default for ConfidenceWeights {
+    fn default() -> Self {
+        Self {
+            schedule: 0.4,
+            cost: 0.4,
+            flow: 0.2,
+        }
+    }
+}
+
+/// Headline figures of `Tasks::summary_json`
+#[derive(Serialize)]
+struct Summary<'a> {
+    name: &'a str,
+    planned_value: f64,
+    actual_cost: f64,
+    earned_value: f64,
+    spi: f64,
+    cpi: f64,
+    completion_percentage: f64,
+    done_tasks: usize,
+    open_tasks: usize,
+    blocked_tasks: usize,
+}
+
+impl EvmMetrics {
+    pub(crate) fn new(planned_value: f64, actual_cost: f64, earned_value: f64) -> Self {
+        Self::with_budget(planned_value, actual_cost, earned_value, planned_value)
+    }
+
+    /// Same as `new`, but the forecasts (`eac`, `vac` and `tcpi`) are made against
+    /// `budget_at_completion` instead of the planned value
+    pub(crate) fn with_budget(planned_value: f64, actual_cost: f64, earned_value: f64, budget_at_completion: f64) -> Self {
+        let cpi = safe_ratio(earned_value, actual_cost);
+        let eac = safe_ratio(budget_at_completion, cpi);
+        Self {
+            planned_value,
+            actual_cost,
+            earned_value,
+            spi: safe_ratio(earned_value, planned_value),
+            sv: earned_value - planned_value,
+            cpi,
+            cv: earned_value - actual_cost,
+            eac,
+            vac: budget_at_completion - eac,
+            tcpi: safe_ratio(budget_at_completion - earned_value, budget_at_completion - actual_cost),
+        }
+    }
+}
+
+impl Tasks {
+
+    /// All the project's earned value management figures, computed in a single pass over the
+    /// tasks, with the earned value following the `EvMode`. The individual methods
+    /// (`earned_value`, `spi`, ...) read from this
+    pub fn metrics(&self) -> EvmMetrics {
+        let earned_value = self.mode_earned_value(self.get(&TaskId::get_root_id()).unwrap());
+        EvmMetrics::with_budget(self.planned_value(), self.actual_cost(), earned_value, self.budget_at_completion())
+    }
+
+    /// Heuristic score from 0 to 1 of how likely the project is to finish well: the weighted mean
+    /// of `min(spi, 1)`, `min(cpi, 1)` and the fraction of open leaf tasks that are neither blocked
+    /// nor unassigned (1 when nothing is open). 0.0 if all weights are zero
+    pub fn completion_confidence(&self, weights: &ConfidenceWeights) -> f64 {
+        let metrics = self.metrics();
+        let (open, stuck) = self.get_todo_tasks()
+            .fold((0, 0), |(open, stuck), task| {
+                let is_stuck = task.status == TaskStatus::Blocked || task.members().next().is_none();
+                (open + 1, stuck + is_stuck as usize)
+            });
+        let flow = 1.0 - safe_ratio(stuck as f64, open as f64);
+        let score = weights.schedule * metrics.spi.min(1.0)
+            + weights.cost * metrics.cpi.min(1.0)
+            + weights.flow * flow;
+        safe_ratio(score, weights.schedule + weights.cost + weights.flow)
+    }
+
+    /// Small JSON object with the project's headline figures and how many leaf tasks are done, not
+    /// done (`open_tasks`, blocked ones included) and blocked, for status dashboards
+    pub fn summary_json(&self) -> String {
+        let metrics = self.metrics();
+        let summary = Summary {
+            name: self.name(),
+            planned_value: metrics.planned_value,
+            actual_cost: metrics.actual_cost,
+            earned_value: metrics.earned_value,
+            spi: metrics.spi,
+            cpi: metrics.cpi,
+            completion_percentage: self.completion_percentage(),
+            done_tasks: self.get_done_tasks().count(),
+            open_tasks: self.get_todo_tasks().count(),
+            blocked_tasks: self.get_blocked_tasks().count(),
+        };
+        serde_json::to_string(&summary).expect("summary always serializes")
+    }
+
+    /// Same as `metrics`, restricted to the tasks under `task_id`, e.g. to follow a workstream.
+    /// Forecasts below the root are made against the task's own planned value, since the target
+    /// is set for the whole project
+    pub fn metrics_for(&self, task_id: &TaskId) -> Result<EvmMetrics, Error> {
+        let task = self.get(task_id)?;
+        if task_id.as_vec().is_empty() {
+            return Ok(self.metrics());
+        }
+        let earned_value = self.mode_earned_value(task);
+        Ok(EvmMetrics::new(task.get_planned_value(), task.get_actual_cost(), earned_value))
+    }
+
+    /// Metrics as they stood at the end of `date`: only leaf tasks done by then count as done,
+    /// and only their actual cost counts as spent. The earned value follows the `EvMode` as in
+    /// `metrics`, but unlike there open tasks earn nothing for their `progress`, since it isn't
+    /// dated and may have been reached after `date`
+    pub fn snapshot_as_of(&self, date: NaiveDate) -> EvmMetrics {
+        let actual_cost = self.get_done_tasks()
+            .filter(|task| Self::done_by(task, date))
+            .map(|task| task.get_actual_cost())
+            .sum();
+        let earned_value = self.earned_value_as_of(self.get(&TaskId::get_root_id()).unwrap(), date);
+        EvmMetrics::with_budget(self.planned_value(), actual_cost, earned_value, self.budget_at_completion())
+    }
+
+    fn done_by(task: &Task, date: NaiveDate) -> bool {
+        task.status == TaskStatus::Done && task.done_date().is_some_and(|done_date| done_date <= date)
+    }
+
+    /// `mode_earned_value` at the end of `date`. A trunk's own done date is when its status rolled
+    /// up, which backdating its leaves doesn't move, so it counts as done once all its leaves are
+    fn earned_value_as_of(&self, task: &Task, date: NaiveDate) -> f64 {
+        match self.earned_value_mode() {
+            EvMode::LeafSum => self.subtree_leaves(task.id())
+                .filter(|leaf| Self::done_by(leaf, date))
+                .map(|leaf| leaf.get_planned_value())
+                .sum(),
+            EvMode::MilestoneOnTrunk if task.id().as_vec().is_empty() => task.child_ids()
+                .map(|child_id| self.earned_value_as_of(self.get(&child_id).unwrap(), date))
+                .sum(),
+            EvMode::MilestoneOnTrunk if self.subtree_leaves(task.id()).all(|leaf| Self::done_by(leaf, date)) => {
+                task.get_planned_value()
+            },
+            EvMode::MilestoneOnTrunk => 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_as_of() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+            ("", "Release"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1]), 4.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2]), 4.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1]), 2.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![2]), 3.0).unwrap();
+        tasks.set_done_date(&TaskId::new(vec![1]), NaiveDate::from_ymd(2022, 10, 3)).unwrap();
+        tasks.set_done_date(&TaskId::new(vec![2]), NaiveDate::from_ymd(2022, 10, 10)).unwrap();
+        assert_eq!(tasks.set_done_date(&TaskId::new(vec![3]), NaiveDate::from_ymd(2022, 10, 10)), Err(crate::prelude::Error::TaskNotDone(TaskId::new(vec![3]))));
+
+        // "Build" was done after the snapshot
+        let snapshot = tasks.snapshot_as_of(NaiveDate::from_ymd(2022, 10, 7));
+        assert_eq!(snapshot, EvmMetrics::new(8.0, 2.0, 4.0));
+        assert_eq!(snapshot.cpi, 2.0);
+        assert_eq!(snapshot.spi, 0.5);
+
+        let snapshot = tasks.snapshot_as_of(NaiveDate::from_ymd(2022, 10, 10));
+        assert_eq!(snapshot.earned_value, tasks.earned_value());
+        assert_eq!(snapshot.actual_cost, tasks.actual_cost());
+        assert_eq!(tasks.snapshot_as_of(NaiveDate::from_ymd(2022, 10, 1)), EvmMetrics::new(8.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn snapshot_as_of_milestones() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 1]), 2.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 2]), 3.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2]), 5.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 2.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 2]), 3.0).unwrap();
+        tasks.set_done_date(&TaskId::new(vec![1, 1]), NaiveDate::from_ymd(2022, 10, 3)).unwrap();
+        tasks.set_done_date(&TaskId::new(vec![1, 2]), NaiveDate::from_ymd(2022, 10, 10)).unwrap();
+        tasks.set_earned_value_mode(EvMode::MilestoneOnTrunk);
+
+        // "Design" was only half done
+        assert_eq!(tasks.snapshot_as_of(NaiveDate::from_ymd(2022, 10, 7)), EvmMetrics::new(10.0, 2.0, 0.0));
+        assert_eq!(tasks.snapshot_as_of(NaiveDate::from_ymd(2022, 10, 10)), EvmMetrics::new(10.0, 5.0, 5.0));
+        assert_eq!(tasks.snapshot_as_of(chrono::Utc::now().naive_utc().date()), tasks.metrics());
+    }
+
+    #[test]
+    fn metrics() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1]), 4.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2]), 6.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1]), 2.0).unwrap();
+
+        let metrics = tasks.metrics();
+        assert_eq!(metrics, EvmMetrics {
+            planned_value: 10.0,
+            actual_cost: 2.0,
+            earned_value: 4.0,
+            spi: 0.4,
+            sv: -6.0,
+            cpi: 2.0,
+            cv: 2.0,
+            eac: 5.0,
+            vac: 5.0,
+            tcpi: 0.75,
+        });
+        assert_eq!(
+            (tasks.earned_value(), tasks.spi(), tasks.sv(), tasks.cpi(), tasks.cv()),
+            (metrics.earned_value, metrics.spi, metrics.sv, metrics.cpi, metrics.cv));
+        assert_eq!((tasks.eac(), tasks.vac(), tasks.tcpi()), (metrics.eac, metrics.vac, metrics.tcpi));
+        assert_eq!(
+            serde_json::to_string(&metrics).unwrap(),
+            r#"{"planned_value":10.0,"actual_cost":2.0,"earned_value":4.0,"spi":0.4,"sv":-6.0,"cpi":2.0,"cv":2.0,"eac":5.0,"vac":5.0,"tcpi":0.75}"#);
+    }
+
+    #[test]
+    fn metrics_for() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+                ("2", "Backend"),
+                ("2", "Frontend"),
+        ]).unwrap();
+        [(vec![1, 1], 2.0), (vec![1, 2], 3.0), (vec![2, 1], 5.0), (vec![2, 2], 7.0)].into_iter().for_each(|(id, value)| {
+            tasks.set_planned_value(&TaskId::new(id), value).unwrap();
+        });
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 1.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![2, 2]), 8.0).unwrap();
+
+        let design = tasks.metrics_for(&TaskId::new(vec![1])).unwrap();
+        let build = tasks.metrics_for(&TaskId::new(vec![2])).unwrap();
+        assert_eq!(design, EvmMetrics::new(5.0, 1.0, 2.0));
+        assert_eq!(build, EvmMetrics::new(12.0, 8.0, 7.0));
+        assert_eq!(design.earned_value + build.earned_value, tasks.earned_value());
+        assert_eq!(tasks.metrics_for(&TaskId::get_root_id()), Ok(tasks.metrics()));
+        assert_eq!(tasks.metrics_for(&TaskId::new(vec![3])), Err(Error::TaskNotFound(TaskId::new(vec![3]))));
+    }
+
+    #[test]
+    fn summary_json() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+            ("", "Release"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1]), 2.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2]), 6.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1]), 4.0).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![3]), &TaskId::new(vec![2])).unwrap();
+
+        let summary = serde_json::from_str::<serde_json::Value>(&tasks.summary_json()).unwrap();
+        assert_eq!(summary, serde_json::json!({
+            "name": "Project",
+            "planned_value": 8.0,
+            "actual_cost": 4.0,
+            "earned_value": 2.0,
+            "spi": 0.25,
+            "cpi": 0.5,
+            "completion_percentage": 1.0 / 3.0,
+            "done_tasks": 1,
+            "open_tasks": 2,
+            "blocked_tasks": 1,
+        }));
+    }
+
+    #[test]
+    fn completion_confidence() {
+        let mut tasks = Tasks::new("Project");
+        let mut members = crate::prelude::Members::new();
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+            ("", "Test"),
+            ("", "Release"),
+        ]).unwrap();
+        (1..=4).for_each(|idx| { tasks.set_planned_value(&TaskId::new(vec![idx]), 5.0).unwrap(); });
+        members.insert("alice".to_string()).unwrap();
+        (1..=4).for_each(|idx| members.assign_task_to_member(TaskId::new(vec![idx]), "alice", &mut tasks).unwrap());
+        let weights = ConfidenceWeights::default();
+
+        // on schedule and budget, nothing stuck
+        (1..=4).for_each(|idx| { tasks.set_actual_cost(&TaskId::new(vec![idx]), 5.0).unwrap(); });
+        assert_eq!(tasks.completion_confidence(&weights), 1.0);
+
+        // one done at twice the cost, the rest waiting on it or nobody's
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[("", "Design"), ("", "Build"), ("", "Test"), ("", "Release")]).unwrap();
+        (1..=4).for_each(|idx| { tasks.set_planned_value(&TaskId::new(vec![idx]), 5.0).unwrap(); });
+        tasks.add_dependency(&TaskId::new(vec![3]), &TaskId::new(vec![2])).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![4]), &TaskId::new(vec![3])).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1]), 10.0).unwrap();
+        // spi = 0.25, cpi = 0.5, flow = 0
+        assert!((tasks.completion_confidence(&weights) - 0.3).abs() < 1e-9);
+        assert!(tasks.completion_confidence(&ConfidenceWeights { schedule: 1.0, cost: 0.0, flow: 0.0 }) < 0.3);
+        assert_eq!(tasks.completion_confidence(&ConfidenceWeights { schedule: 0.0, cost: 0.0, flow: 0.0 }), 0.0);
+    }
+}
@@ -1,8 +1,13 @@
 pub mod task_id;
 pub(crate) mod tasks;
+pub(crate) mod template;
+pub(crate) mod export;
+pub(crate) mod metrics;
+pub(crate) mod store;
 
 use std::{fmt::Display, collections::HashSet};
 
+use chrono::NaiveDate;
 use serde::{Serialize, Deserialize};
 use serde_with::serde_as;
 
@@ -12,6 +17,7 @@ use self::task_id::TaskId;
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum TaskStatus {
     InProgress,
+    Blocked,
     Done
 }
 
@@ -19,6 +25,7 @@ impl Display for TaskStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             TaskStatus::InProgress => write!(f, "InProgress"),
+            TaskStatus::Blocked => write!(f, "Blocked"),
             TaskStatus::Done => write!(f, "Done"),
         }
     }
@@ -28,24 +35,49 @@ impl TaskStatus {
     pub(crate) fn to_icon(&self) -> &'static str {
         match &self {
             TaskStatus::InProgress => "✗",
+            TaskStatus::Blocked => "⊘",
             TaskStatus::Done => "✔"
         }
     }
+
+    pub(crate) fn to_color(&self) -> &'static str {
+        match &self {
+            TaskStatus::InProgress => "black",
+            TaskStatus::Blocked => "darkorange",
+            TaskStatus::Done => "darkgreen"
+        }
+    }
 }
 
 #[serde_as]
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Task {
     name: String,
+    #[serde(default)]
+    pub(crate) description: Option<String>,
     pub(crate) id: TaskId,
     pub(crate) planned_value: f64,
     pub(crate) actual_cost: f64,
+    /// how far along the task is, from 0 to 1, while it isn't done
+    #[serde(default)]
+    pub(crate) progress: f64,
     pub(crate) num_child: u32,
     pub(crate) status: TaskStatus,
     #[serde_as(as = "HashSet<_>")]
     pub(crate) dependencies: HashSet<TaskId>,
     #[serde_as(as = "HashSet<_>")]
     pub(crate) dependency_for: HashSet<TaskId>,
+    #[serde(default)]
+    pub(crate) start_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub(crate) due_date: Option<NaiveDate>,
+    /// when the task was last marked done
+    #[serde(default)]
+    pub(crate) done_date: Option<NaiveDate>,
+    /// names of the members assigned to it, mirroring their own task lists
+    #[serde_as(as = "HashSet<_>")]
+    #[serde(default)]
+    pub(crate) members: HashSet<String>,
 }
 
 impl Eq for Task {}
@@ -61,12 +93,18 @@ impl Task {
         Self {
             id,
             name: name.to_string(),
+            description: None,
             planned_value: 0.0,
             actual_cost: 0.0,
+            progress: 0.0,
             num_child: 0,
             status: TaskStatus::InProgress,
             dependencies: HashSet::new(),
             dependency_for: HashSet::new(),
+            start_date: None,
+            due_date: None,
+            done_date: None,
+            members: HashSet::new(),
         }
     }
 
@@ -78,6 +116,10 @@ impl Task {
         &self.name
     }
 
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
     pub fn get_planned_value(&self) -> f64 {
         self.planned_value
     }
@@ -86,6 +128,48 @@ impl Task {
         self.actual_cost
     }
 
+    pub fn progress(&self) -> f64 {
+        self.progress
+    }
+
+    pub fn start_date(&self) -> Option<NaiveDate> {
+        self.start_date
+    }
+
+    pub fn due_date(&self) -> Option<NaiveDate> {
+        self.due_date
+    }
+
+    pub fn done_date(&self) -> Option<NaiveDate> {
+        self.done_date
+    }
+
+    /// Names of the members assigned to the task, in no particular order
+    pub fn members(&self) -> impl Iterator<Item=&str> {
+        self.members.iter().map(|name| name.as_str())
+    }
+
+    pub fn has_member(&self, name: &str) -> bool {
+        self.members.contains(name)
+    }
+
+    pub(crate) fn add_member(&mut self, name: &str) {
+        self.members.insert(name.to_string());
+    }
+
+    pub(crate) fn remove_member(&mut self, name: &str) {
+        self.members.remove(name);
+    }
+
+    /// Planned value earned by this task so far: all of it once done, its share by progress
+    /// before that
+    pub fn get_earned_value(&self) -> f64 {
+        match self.status {
+            TaskStatus::Done => self.planned_value,
+            TaskStatus::InProgress | TaskStatus::Blocked => self.planned_value * self.progress,
+        }
+    }
+
     pub fn child_ids(&self) -> impl Iterator<Item=TaskId> + '_ {
         self.id().child_ids(self.num_child)
     }
@@ -0,0 +1,162 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Serialize, Deserialize, Serializer, Deserializer};
+
+use super::{Task, task_id::TaskId};
+
+/// Container behind `Tasks`. Hashed lookups are the default, while the ordered one keeps tasks
+/// sorted by id so traversals don't have to sort them first
+#[derive(Debug, Clone)]
+pub(crate) enum TaskStore {
+    Hashed(HashMap<TaskId, Task>),
+    Ordered(BTreeMap<TaskId, Task>),
+}
+
+/// Iterator over either kind of store
+enum Iter<H, O> {
+    Hashed(H),
+    Ordered(O),
+}
+
+impl<T, H: Iterator<Item=T>, O: Iterator<Item=T>> Iterator for Iter<H, O> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        match self {
+            Iter::Hashed(iter) => iter.next(),
+            Iter::Ordered(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Iter::Hashed(iter) => iter.size_hint(),
+            Iter::Ordered(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl TaskStore {
+
+    /// Empty store of the same kind as this one
+    pub(crate) fn empty_like(&self) -> Self {
+        match self {
+            TaskStore::Hashed(_) => TaskStore::Hashed(HashMap::new()),
+            TaskStore::Ordered(_) => TaskStore::Ordered(BTreeMap::new()),
+        }
+    }
+
+    /// Same tasks, kept sorted by id
+    pub(crate) fn into_ordered(self) -> Self {
+        match self {
+            TaskStore::Hashed(store) => TaskStore::Ordered(store.into_iter().collect()),
+            ordered => ordered,
+        }
+    }
+
+    pub(crate) fn is_ordered(&self) -> bool {
+        matches!(self, TaskStore::Ordered(_))
+    }
+
+    #[cfg(test)]
+    pub(crate) fn capacity(&self) -> usize {
+        match self {
+            TaskStore::Hashed(store) => store.capacity(),
+            TaskStore::Ordered(store) => store.len(),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            TaskStore::Hashed(store) => store.len(),
+            TaskStore::Ordered(store) => store.len(),
+        }
+    }
+
+    pub(crate) fn get(&self, task_id: &TaskId) -> Option<&Task> {
+        match self {
+            TaskStore::Hashed(store) => store.get(task_id),
+            TaskStore::Ordered(store) => store.get(task_id),
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, task_id: &TaskId) -> Option<&mut Task> {
+        match self {
+            TaskStore::Hashed(store) => store.get_mut(task_id),
+            TaskStore::Ordered(store) => store.get_mut(task_id),
+        }
+    }
+
+    pub(crate) fn contains_key(&self, task_id: &TaskId) -> bool {
+        self.get(task_id).is_some()
+    }
+
+    pub(crate) fn insert(&mut self, task_id: TaskId, task: Task) -> Option<Task> {
+        match self {
+            TaskStore::Hashed(store) => store.insert(task_id, task),
+            TaskStore::Ordered(store) => store.insert(task_id, task),
+        }
+    }
+
+    pub(crate) fn remove(&mut self, task_id: &TaskId) -> Option<Task> {
+        match self {
+            TaskStore::Hashed(store) => store.remove(task_id),
+            TaskStore::Ordered(store) => store.remove(task_id),
+        }
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item=(&TaskId, &Task)> {
+        match self {
+            TaskStore::Hashed(store) => Iter::Hashed(store.iter()),
+            TaskStore::Ordered(store) => Iter::Ordered(store.iter()),
+        }
+    }
+
+    pub(crate) fn keys(&self) -> impl Iterator<Item=&TaskId> {
+        self.iter().map(|(id, _)| id)
+    }
+
+    pub(crate) fn values(&self) -> impl Iterator<Item=&Task> {
+        self.iter().map(|(_, task)| task)
+    }
+
+    pub(crate) fn values_mut(&mut self) -> impl Iterator<Item=&mut Task> {
+        match self {
+            TaskStore::Hashed(store) => Iter::Hashed(store.values_mut()),
+            TaskStore::Ordered(store) => Iter::Ordered(store.values_mut()),
+        }
+    }
+
+    /// Tasks in id order. Only the hashed store has to collect and sort them for that
+    pub(crate) fn sorted_values(&self) -> impl Iterator<Item=&Task> {
+        match self {
+            TaskStore::Hashed(store) => {
+                let mut tasks = store.values().collect::<Vec<&Task>>();
+                tasks.sort_by(|a, b| a.id().cmp(b.id()));
+                Iter::Hashed(tasks.into_iter())
+            },
+            TaskStore::Ordered(store) => Iter::Ordered(store.values()),
+        }
+    }
+}
+
+impl PartialEq for TaskStore {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|(id, task)| other.get(id) == Some(task))
+    }
+}
+
+/// Stored as a list of `(id, task)` pairs whatever the kind, so project files don't depend on it.
+/// Loading always gives a hashed store, `Tasks` keeps the kind next to the list to restore it
+impl Serialize for TaskStore {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for TaskStore {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pairs = Vec::<(TaskId, Task)>::deserialize(deserializer)?;
+        Ok(TaskStore::Hashed(pairs.into_iter().collect()))
+    }
+}
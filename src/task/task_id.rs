@@ -4,8 +4,10 @@ use serde::{Serialize, Deserialize};
 
 use crate::error::Error;
 
+/// Ids are ordered element-wise, so `1.2` comes before `1.10` and a task comes right before its
+/// subtasks, as in a pre-order traversal
 #[derive(Serialize, Deserialize)]
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct TaskId {
     id: Vec<u32>,
 }
@@ -48,14 +50,20 @@ impl TaskId {
     }
 
     pub fn parse(id: &str) -> Result<Self, Error> {
+        Self::parse_with_sep(id, '.')
+    }
+
+    /// Same as `parse`, for ids whose numbers are separated by `sep` instead of dots
+    pub fn parse_with_sep(id: &str, sep: char) -> Result<Self, Error> {
         if id.is_empty() {
             return Ok(TaskId::new(vec![]));
         }
+        // child indices start at 1, and ones that don't fit a `u32` fail to parse
         let vec = id
-            .split(".")
-            .map(|n| n.parse::<u32>())
-            .collect::<Result<Vec<u32>, _>>()
-            .or_else(|_| Err(Error::BadTaskIdString(id.to_string())))?;
+            .split(sep)
+            .map(|n| n.parse::<u32>().ok().filter(|&n| n > 0))
+            .collect::<Option<Vec<u32>>>()
+            .ok_or_else(|| Error::BadTaskIdString(id.to_string()))?;
         Ok(TaskId::new(vec))
     }
 
@@ -68,16 +76,28 @@ impl TaskId {
         Ok(TaskId::new(parent_vec))
     }
 
+    /// Same id with the last component incremented
+    pub fn with_last_incremented(&self) -> Result<TaskId, Error> {
+        let child_idx = self.child_idx()?
+            .checked_add(1)
+            .ok_or(Error::BadTaskIdNum)?;
+        self.parent()?.new_child_id(child_idx)
+    }
+
+    /// Same id with the last component decremented, which can't go below 1
+    pub fn with_last_decremented(&self) -> Result<TaskId, Error> {
+        let child_idx = self.child_idx()?
+            .checked_sub(1)
+            .ok_or(Error::BadTaskIdNum)?;
+        self.parent()?.new_child_id(child_idx)
+    }
+
     pub(crate) fn prev_sibling(&self) -> Result<TaskId, Error> {
-        let parent_id = self.parent()?;
-        let child_idx = self.child_idx()?;
-        parent_id.new_child_id(child_idx-1)
+        self.with_last_decremented()
     }
 
     pub(crate) fn next_sibling(&self) -> Result<TaskId, Error> {
-        let parent_id = self.parent()?;
-        let child_idx = self.child_idx()?;
-        parent_id.new_child_id(child_idx+1)
+        self.with_last_incremented()
     }
 
     pub fn child_ids(&self, num_childs: u32) -> impl Iterator<Item=TaskId> + '_ {
@@ -121,15 +141,31 @@ impl TaskId {
     pub fn get_root_id() -> TaskId {
         TaskId::new(vec![])
     }
+
+    /// Whether `other` is somewhere under this id, not counting itself. The root is an ancestor of
+    /// every other id
+    pub fn is_ancestor_of(&self, other: &TaskId) -> bool {
+        other.len() > self.len() && other.id.starts_with(&self.id)
+    }
+
+    /// Whether this id is somewhere under `other`, not counting itself
+    pub fn is_descendant_of(&self, other: &TaskId) -> bool {
+        other.is_ancestor_of(self)
+    }
+
+    /// Same as `to_string`, with the numbers separated by `sep` instead of dots
+    pub fn to_string_with_sep(&self, sep: char) -> String {
+        self.id
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<String>>()
+            .join(&sep.to_string())
+    }
 }
 
 impl Display for TaskId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.id
-               .iter()
-               .map(|n| n.to_string())
-               .collect::<Vec<String>>()
-               .join("."))
+        write!(f, "{}", self.to_string_with_sep('.'))
     }
 }
 
@@ -159,6 +195,41 @@ mod tests {
         assert_eq!(TaskId::parse("4.523.123").unwrap().as_vec(), &vec![4, 523, 123]);
         assert!(TaskId::parse(".1.1").is_err());
         assert!(TaskId::parse("1.1.").is_err());
+        for id in ["0", "1.0.2", "4294967296", "1.99999999999999999999"] {
+            assert_eq!(TaskId::parse(id), Err(Error::BadTaskIdString(id.to_string())));
+        }
+        assert_eq!(TaskId::parse("4294967295").unwrap().as_vec(), &vec![u32::MAX]);
+    }
+
+    #[test]
+    fn separators() {
+        for sep in ['/', '-'] {
+            let id = TaskId::parse_with_sep(&format!("1{sep}2{sep}3"), sep).unwrap();
+            assert_eq!(id, TaskId::new(vec![1, 2, 3]));
+            assert_eq!(id.to_string_with_sep(sep), format!("1{sep}2{sep}3"));
+            assert_eq!(id.to_string(), "1.2.3");
+            assert_eq!(TaskId::parse_with_sep("", sep), Ok(TaskId::get_root_id()));
+            assert_eq!(TaskId::get_root_id().to_string_with_sep(sep), "");
+            assert_eq!(TaskId::parse_with_sep("1.2", sep), Err(Error::BadTaskIdString("1.2".to_string())));
+            assert!(TaskId::parse_with_sep(&format!("1{sep}"), sep).is_err());
+        }
+    }
+
+    #[test]
+    fn ancestry() {
+        let id = |s| TaskId::parse(s).unwrap();
+        assert!(id("1").is_ancestor_of(&id("1.2")));
+        assert!(id("1").is_ancestor_of(&id("1.2.3")));
+        assert!(!id("1").is_ancestor_of(&id("2")));
+        assert!(!id("1").is_ancestor_of(&id("12")));
+        assert!(!id("1.2").is_ancestor_of(&id("1")));
+        assert!(!id("1").is_ancestor_of(&id("1")));
+        assert!(id("").is_ancestor_of(&id("1.1")));
+        assert!(!id("").is_ancestor_of(&id("")));
+        assert!(id("1.2").is_descendant_of(&id("1")));
+        assert!(id("1.1").is_descendant_of(&id("")));
+        assert!(!id("2").is_descendant_of(&id("1")));
+        assert!(!id("").is_descendant_of(&id("")));
     }
 
     #[test]
@@ -167,4 +238,28 @@ mod tests {
         assert_eq!(TaskId::parse("1.1.234.12").unwrap().parent().unwrap().as_vec(), &vec![1,1,234]);
         assert_eq!(TaskId::parse("2.534.234.12.243.123").unwrap().parent().unwrap().as_vec(), &vec![2, 534, 234, 12, 243]);
     }
+
+    #[test]
+    fn with_last_adjusted() {
+        assert_eq!(TaskId::new(vec![1, 2]).with_last_incremented(), Ok(TaskId::new(vec![1, 3])));
+        assert_eq!(TaskId::new(vec![1, 2]).with_last_decremented(), Ok(TaskId::new(vec![1, 1])));
+        assert_eq!(TaskId::new(vec![1, 1]).with_last_decremented(), Err(Error::BadTaskIdNum));
+        assert_eq!(TaskId::new(vec![u32::MAX]).with_last_incremented(), Err(Error::BadTaskIdNum));
+        assert_eq!(TaskId::get_root_id().with_last_incremented(), Err(Error::NoChildIndex(TaskId::get_root_id())));
+        assert_eq!(TaskId::get_root_id().with_last_decremented(), Err(Error::NoChildIndex(TaskId::get_root_id())));
+    }
+
+    #[test]
+    fn ordering() {
+        let id = |s| TaskId::parse(s).unwrap();
+        assert!(id("1") < id("1.1"));
+        assert!(id("") < id("1"));
+        assert!(id("1.2") < id("1.10"));
+        assert!(id("1.9.5") < id("1.10"));
+        assert!(id("3.1.2") < id("3.1.3"));
+        assert!(id("2.1") > id("1.7.7"));
+        let mut ids = vec![id("2"), id("1.10"), id("1"), id("1.2.1"), id("1.2")];
+        ids.sort();
+        assert_eq!(ids, vec![id("1"), id("1.2"), id("1.2.1"), id("1.10"), id("2")]);
+    }
 }
@@ -1,34 +1,174 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, hash_map::DefaultHasher};
+use std::hash::{Hash, Hasher};
+use std::path::Path;
 
+use chrono::{NaiveDate, Utc};
 use serde::{Serialize, Deserialize};
-use serde_with::serde_as;
 
 use crate::prelude::{TaskId, Error, Members};
 
-use super::{Task, TaskStatus};
+use super::{Task, TaskStatus, template::Template, metrics::{EvMode, safe_ratio}, store::TaskStore};
 
-#[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tasks {
-    #[serde_as(as="Vec<(_, _)>")]
-    store: HashMap<TaskId, Task>
+    store: TaskStore,
+    /// whether `store` is ordered, since it always loads as a hashed one
+    #[serde(default)]
+    ordered: bool,
+    /// maintained incrementally, rebuilt by `recount` after loading
+    #[serde(skip)]
+    leaf_count: usize,
+    #[serde(skip)]
+    done_count: usize,
+    #[serde(default)]
+    earned_value_mode: EvMode,
+    /// top-down budget for the whole project, to compare against the rolled-up planned value
+    #[serde(default)]
+    target_planned_value: Option<f64>,
 }
 
-enum DFSVertexStatus {
-    Done,
-    InStack,
-    NotVisited
+/// FNV-1a hasher, for hashes that must not change between runs
+struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+/// Make a string safe to put between double quotes in DOT
+pub(crate) fn escape_dot_label(label: &str) -> String {
+    label
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
 }
 
 impl Tasks {
     pub(crate) fn new(name: &str) -> Self {
-        let mut store = HashMap::new();
+        Self::with_capacity(name, 0)
+    }
+
+    /// Same as `new`, but tasks are kept sorted by id, so traversals in id order (like `get_tasks`)
+    /// don't need to sort them first, at the cost of slower lookups
+    pub fn new_ordered(name: &str) -> Self {
+        Self::with_store(name, TaskStore::Ordered(BTreeMap::new()))
+    }
+
+    /// Preallocate room for `capacity` tasks, so bulk imports of known size don't reallocate
+    pub(crate) fn with_capacity(name: &str, capacity: usize) -> Self {
+        Self::with_store(name, TaskStore::Hashed(HashMap::with_capacity(capacity)))
+    }
+
+    fn with_store(name: &str, store: TaskStore) -> Self {
         let root_id = TaskId::get_root_id();
         let root_task = Task::new(root_id.clone(), name);
-        store.insert(root_id, root_task);
-        Self {
-            store
+        let mut tasks = Self {
+            ordered: store.is_ordered(),
+            store,
+            leaf_count: 0,
+            done_count: 0,
+            earned_value_mode: EvMode::default(),
+            target_planned_value: None,
+        };
+        tasks.insert(root_id, root_task);
+        tasks
+    }
+
+    /// Tasks as a JSON array, in pre-order. Each task carries its own id, so unlike in project
+    /// files they aren't stored as keys. Only the tasks are written: the `EvMode` and the target
+    /// are kept by project files, and `from_json` starts without a target, in the default mode
+    pub fn to_json(&self) -> Result<String, Error> {
+        let tasks = self.dfs_ids()
+            .iter()
+            .map(|id| self.get(id))
+            .collect::<Result<Vec<&Task>, Error>>()?;
+        serde_json::to_string(&tasks)
+            .map_err(|_| Error::ParseProjectContents)
+    }
+
+    /// Load tasks written by `to_json`, refusing any tree `validate` complains about
+    pub fn from_json(tasks_str: &str) -> Result<Self, Error> {
+        let store = serde_json::from_str::<Vec<Task>>(tasks_str)
+            .map_err(|_| Error::ParseJsonContents(tasks_str.to_string()))?
+            .into_iter()
+            .map(|task| (task.id.clone(), task))
+            .collect::<HashMap<TaskId, Task>>();
+        let mut tasks = Self {
+            store: TaskStore::Hashed(store),
+            ordered: false,
+            leaf_count: 0,
+            done_count: 0,
+            earned_value_mode: EvMode::default(),
+            target_planned_value: None,
+        };
+        tasks.get(&TaskId::get_root_id())?;
+        if let Some(error) = tasks.validate().into_iter().next() {
+            return Err(error);
+        }
+        tasks.recount();
+        Ok(tasks)
+    }
+
+    /// Write `to_json` to a file, creating its missing parent directories
+    pub fn save_to_file(&self, path: &Path) -> Result<(), Error> {
+        let filename = path.display().to_string();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|_| Error::FileWrite(filename.clone()))?;
+        }
+        std::fs::write(path, self.to_json()?)
+            .map_err(|_| Error::FileWrite(filename))
+    }
+
+    /// Read tasks written by `save_to_file`
+    pub fn load_from_file(path: &Path) -> Result<Self, Error> {
+        let tasks_str = std::fs::read_to_string(path)
+            .map_err(|_| Error::FileRead(path.display().to_string()))?;
+        Self::from_json(&tasks_str)
+    }
+
+    /// Bring back what deserializing doesn't: the ordered store, if the tasks were kept in one, and
+    /// the counts
+    pub(crate) fn restore(&mut self) {
+        if self.ordered && !self.store.is_ordered() {
+            let store = std::mem::replace(&mut self.store, TaskStore::Ordered(BTreeMap::new()));
+            self.store = store.into_ordered();
         }
+        self.recount();
+    }
+
+    /// Rebuild the leaf and done counts from a full scan, e.g. after deserializing
+    pub(crate) fn recount(&mut self) {
+        self.leaf_count = self.store.values().filter(|task| Self::is_leaf_task(task)).count();
+        self.done_count = self.get_done_tasks().count();
+    }
+
+    /// Number of tasks, trunks and the root included, like `len`
+    pub fn task_count(&self) -> usize {
+        self.store.len()
+    }
+
+    /// Number of leaf tasks, the denominator of `completion_percentage`
+    pub fn leaf_count(&self) -> usize {
+        self.leaf_count
+    }
+
+    pub fn done_count(&self) -> usize {
+        self.done_count
     }
 
     /// SAFETY: uses `unwrap` instead of returning an error because a root node should always
@@ -49,63 +189,665 @@ impl Tasks {
         self.get(&TaskId::get_root_id()).unwrap().get_actual_cost()
     }
 
+    /// Top-down budget set for the project, if any
+    pub fn target(&self) -> Option<f64> {
+        self.target_planned_value
+    }
+
+    pub(crate) fn set_target(&mut self, target: Option<f64>) {
+        self.target_planned_value = target;
+    }
+
+    /// Budget the forecasts (`eac`, `vac` and `tcpi`) are made against: the target when one is set,
+    /// otherwise the planned value rolled up from the tasks
+    pub fn budget_at_completion(&self) -> f64 {
+        self.target_planned_value.unwrap_or_else(|| self.planned_value())
+    }
+
+    /// How much the target exceeds the planned value rolled up from the tasks: positive when the
+    /// estimates leave part of the budget unused, negative when they overrun it. `None` without a
+    /// target
+    pub fn budget_variance(&self) -> Option<f64> {
+        self.target_planned_value.map(|target| target - self.planned_value())
+    }
+
+    /// Fraction of the leaf tasks that are done, each counting the same whatever its size. See
+    /// `value_completion_percentage` for the fraction of the planned value
     pub fn completion_percentage(&self) -> f64 {
-        self.get_done_tasks().count() as f64 / self.len() as f64
+        safe_ratio(self.done_count as f64, self.leaf_count as f64)
+    }
+
+    /// Fraction of the planned value of the leaf tasks that has been earned, so finishing a big task
+    /// counts for more than finishing a small one. Done leaves earn all of their planned value, and
+    /// open ones the part of it their `progress` covers
+    pub fn value_completion_percentage(&self) -> f64 {
+        safe_ratio(self.earned_value(), self.planned_value())
     }
 
+    /// Planned value of the work done so far, as in `value_completion_percentage`: all of it for
+    /// done leaves and the `progress` share for open ones, following the `EvMode`
     pub fn earned_value(&self) -> f64 {
-        self.planned_value() * self.completion_percentage()
+        self.metrics().earned_value
     }
 
+    /// 0.0 when there's no planned value to compare against
     pub fn spi(&self) -> f64 {
-        let res = self.earned_value() / self.planned_value();
-        if res.is_nan() {
-            0.0
-        } else {
-            res
-        }
+        self.metrics().spi
+    }
+
+    /// Schedule performance index using earned value weighted by planned value: the planned value
+    /// of done leaf tasks over the total planned value, so finishing only the cheap tasks doesn't
+    /// make the project look on schedule. Since `earned_value` is weighted the same way, this is
+    /// the same as `spi`
+    #[deprecated(note = "`earned_value` is already weighted by planned value, use `spi`")]
+    pub fn spi_weighted(&self) -> f64 {
+        self.metrics().spi
     }
 
     pub fn sv(&self) -> f64 {
-        self.earned_value() - self.planned_value()
+        self.metrics().sv
     }
 
+    /// 0.0 when nothing has been spent yet, even if some value was already earned
     pub fn cpi(&self) -> f64 {
-        let res = self.earned_value() / self.actual_cost();
-        if res.is_nan() {
-            0.0
-        } else {
-            res
-        }
+        self.metrics().cpi
     }
 
     pub fn cv(&self) -> f64 {
-        self.earned_value() - self.actual_cost()
+        self.metrics().cv
+    }
+
+    /// Estimate at completion: the `budget_at_completion` scaled by the cost performance so far.
+    /// 0.0 while `cpi` is 0.0
+    pub fn eac(&self) -> f64 {
+        self.metrics().eac
+    }
+
+    /// Variance at completion: how much under (positive) or over (negative) budget the project is
+    /// expected to finish
+    pub fn vac(&self) -> f64 {
+        self.metrics().vac
+    }
+
+    /// To-complete performance index: the cost performance the remaining work needs to finish on
+    /// budget. Negative once the budget is overrun, and 0.0 when it's spent exactly
+    pub fn tcpi(&self) -> f64 {
+        self.metrics().tcpi
+    }
+
+    /// Recompute each trunk's planned value and actual cost from its children and panic if the
+    /// stored roll-up drifted from them. Trunks are checked bottom-up, so the deepest drift is the
+    /// one reported. Does nothing without `debug_assertions`
+    pub fn debug_assert_rollups(&self) {
+        const EPSILON: f64 = 1e-6;
+        let ids = self.dfs_ids();
+        ids.iter().rev().filter_map(|id| self.get(id).ok()).filter(|task| task.is_trunk()).for_each(|task| {
+            let (planned_value, actual_cost) = task
+                .child_ids()
+                .filter_map(|id| self.get(&id).ok())
+                .fold((0.0, 0.0), |(planned_value, actual_cost), child| {
+                    (planned_value + child.planned_value, actual_cost + child.actual_cost)
+                });
+            debug_assert!(
+                (task.planned_value - planned_value).abs() <= EPSILON,
+                "planned value of {} is {}, but its children add up to {}", task.id, task.planned_value, planned_value);
+            debug_assert!(
+                (task.actual_cost - actual_cost).abs() <= EPSILON,
+                "actual cost of {} is {}, but its children add up to {}", task.id, task.actual_cost, actual_cost);
+        });
+    }
+
+    /// Set each trunk's planned value and actual cost to the sum of its children's, bottom-up, so
+    /// they add up to their leaves again after bulk edits or a drifted roll-up
+    pub(crate) fn recompute_aggregates(&mut self) {
+        self.dfs_ids().iter().rev().for_each(|id| {
+            let task = self.get(id).unwrap();
+            if task.is_leaf() {
+                return;
+            }
+            let (planned_value, actual_cost) = task
+                .child_ids()
+                .filter_map(|id| self.get(&id).ok())
+                .fold((0.0, 0.0), |(planned_value, actual_cost), child| {
+                    (planned_value + child.planned_value, actual_cost + child.actual_cost)
+                });
+            let task = self.store.get_mut(id).unwrap();
+            task.planned_value = planned_value;
+            task.actual_cost = actual_cost;
+        });
+    }
+
+    /// Every structural problem in the task tree: tasks stored under the wrong id, tasks
+    /// unreachable from their parent, missing children, tasks depending on themselves and
+    /// dependencies recorded on one side only
+    pub fn validate(&self) -> Vec<Error> {
+        self.inconsistencies().collect()
+    }
+
+    /// Whether `validate` would find no problems, stopping at the first one found
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistencies().next().is_none()
+    }
+
+    fn inconsistencies(&self) -> impl Iterator<Item=Error> + '_ {
+        self.store
+            .iter()
+            .flat_map(|(key, task)| self.task_inconsistencies(key, task))
+    }
+
+    fn task_inconsistencies<'a>(&'a self, key: &'a TaskId, task: &'a Task) -> impl Iterator<Item=Error> + 'a {
+        let mismatched_id = (key != task.id())
+            .then(|| Error::MismatchedTaskId(key.clone()));
+
+        // non-root tasks must be within their parent's children count
+        let orphan = key.parent().ok().and_then(|parent_id| {
+            let reachable = self.store
+                .get(&parent_id)
+                .is_some_and(|parent| key.child_idx().is_ok_and(|idx| idx <= parent.num_child));
+            (!reachable).then(|| Error::OrphanTask(key.clone()))
+        });
+
+        let missing_children = task
+            .child_ids()
+            .filter(|child_id| !self.store.contains_key(child_id))
+            .map(|child_id| Error::MissingChild(key.clone(), child_id));
+
+        let self_dependency = (task.dependencies.contains(key) || task.dependency_for.contains(key))
+            .then(|| Error::SelfDependency(key.clone()));
+
+        let dangling_dependencies = task.dependencies
+            .iter()
+            .filter(|id| !self.store.get(id).is_some_and(|other| other.dependency_for.contains(key)))
+            .chain(task.dependency_for
+                .iter()
+                .filter(|id| !self.store.get(id).is_some_and(|other| other.dependencies.contains(key))))
+            .map(|id| Error::DanglingDependency(key.clone(), id.clone()));
+
+        mismatched_id
+            .into_iter()
+            .chain(orphan)
+            .chain(missing_children)
+            .chain(self_dependency)
+            .chain(dangling_dependencies)
+    }
+
+    /// Leaf tasks not done yet with the planned value each keeps out of the earned value, the
+    /// biggest drags on `spi` first (ties in id order)
+    pub fn spi_contributors(&self) -> Vec<(TaskId, f64)> {
+        let mut contributors = self.get_todo_tasks()
+            .map(|task| (task.id().clone(), task.get_planned_value()))
+            .collect::<Vec<(TaskId, f64)>>();
+        contributors.sort_by(|(a_id, a_value), (b_id, b_value)| b_value.total_cmp(a_value).then_with(|| a_id.cmp(b_id)));
+        contributors
+    }
+
+    /// Schedule performance index times cost performance index of a single task, where the earned
+    /// value is that of the leaf tasks under it. Values well below 1 flag a troubled task
+    pub fn critical_ratio(&self, task_id: &TaskId) -> Result<f64, Error> {
+        let task = self.get(task_id)?;
+        let earned_value = self.subtree_earned_value(task_id)?;
+        Ok(safe_ratio(earned_value, task.get_planned_value()) * safe_ratio(earned_value, task.get_actual_cost()))
+    }
+
+    /// Every task under a task, not counting itself, in pre-order. Empty for leaves and unknown ids
+    pub fn descendants(&self, task_id: &TaskId) -> impl Iterator<Item=&Task> {
+        let mut ids = Vec::new();
+        self.subtree_dfs_ids(task_id, &mut ids);
+        ids.into_iter()
+            .skip(1)
+            .map(|id| self.get(&id).unwrap())
+    }
+
+    /// Ids of the leaf tasks in the order `to_tree_str` lays them out (pre-order), e.g. for Gantt
+    /// rows
+    pub fn ordered_leaf_ids(&self) -> Vec<TaskId> {
+        self.subtree_leaves(&TaskId::get_root_id())
+            .map(|task| task.id().clone())
+            .collect()
+    }
+
+    /// Leaf tasks under a task (or the task itself, if it's a leaf), in pre-order
+    pub(crate) fn subtree_leaves(&self, task_id: &TaskId) -> impl Iterator<Item=&Task> {
+        let mut ids = Vec::new();
+        self.subtree_dfs_ids(task_id, &mut ids);
+        ids.into_iter()
+            .map(|id| self.get(&id).unwrap())
+            .filter(|task| Self::is_leaf_task(task))
+    }
+
+    /// Fraction of the leaf tasks under a task (or the task itself, if it's a leaf) that are done
+    pub fn subtree_completion(&self, task_id: &TaskId) -> Result<f64, Error> {
+        self.get(task_id)?;
+        let (done, leaves) = self.subtree_leaves(task_id)
+            .fold((0, 0), |(done, leaves), task| {
+                (done + (task.status == TaskStatus::Done) as usize, leaves + 1)
+            });
+        Ok(safe_ratio(done as f64, leaves as f64))
+    }
+
+    /// Direct children of a task paired with their subtree completion, the furthest along first
+    pub fn children_by_completion(&self, parent_id: &TaskId) -> Result<Vec<(&Task, f64)>, Error> {
+        let mut children = self.get(parent_id)?
+            .child_ids()
+            .map(|id| Ok((self.get(&id)?, self.subtree_completion(&id)?)))
+            .collect::<Result<Vec<(&Task, f64)>, Error>>()?;
+        children.sort_by(|(a, a_completion), (b, b_completion)| {
+            b_completion.total_cmp(a_completion).then_with(|| a.id().cmp(b.id()))
+        });
+        Ok(children)
+    }
+
+    /// Earned value of a task, according to the `EvMode`. For leaves it's their planned value
+    /// once done, under either mode
+    pub fn subtree_earned_value(&self, task_id: &TaskId) -> Result<f64, Error> {
+        Ok(self.mode_earned_value(self.get(task_id)?))
+    }
+
+    /// Earned value of a task under the `EvMode`, which every other earned value figure goes
+    /// through. The root isn't a milestone itself: under `MilestoneOnTrunk` the project earns
+    /// what its top-level tasks do
+    pub(crate) fn mode_earned_value(&self, task: &Task) -> f64 {
+        match self.earned_value_mode {
+            EvMode::LeafSum => self.subtree_leaves(task.id())
+                .map(|leaf| leaf.get_earned_value())
+                .sum(),
+            EvMode::MilestoneOnTrunk if task.id().as_vec().is_empty() => task.child_ids()
+                .map(|child_id| self.mode_earned_value(self.get(&child_id).unwrap()))
+                .sum(),
+            EvMode::MilestoneOnTrunk => task.get_earned_value(),
+        }
+    }
+
+    pub fn earned_value_mode(&self) -> EvMode {
+        self.earned_value_mode
+    }
+
+    pub(crate) fn set_earned_value_mode(&mut self, mode: EvMode) {
+        self.earned_value_mode = mode;
+    }
+
+    /// Minimum, maximum and mean depth of the leaf tasks. Leaves at very different depths hint at
+    /// a lopsided decomposition
+    pub fn leaf_depth_stats(&self) -> (usize, usize, f64) {
+        let depths = self.get_tasks().map(|task| task.id().len()).collect::<Vec<usize>>();
+        let min = depths.iter().cloned().min().unwrap_or(0);
+        let max = depths.iter().cloned().max().unwrap_or(0);
+        let mean = safe_ratio(depths.iter().sum::<usize>() as f64, depths.len() as f64);
+        (min, max, mean)
+    }
+
+    /// Fraction of each member's assigned leaf tasks that are done. Members without assigned
+    /// tasks are left out
+    pub fn member_completion(&self, members: &Members) -> HashMap<String, f64> {
+        members
+            .members()
+            .filter_map(|member| {
+                let (done, assigned) = member
+                    .task_ids()
+                    .filter_map(|id| self.get(id).ok())
+                    .filter(|task| Self::is_leaf_task(task))
+                    .fold((0, 0), |(done, assigned), task| {
+                        (done + (task.status == TaskStatus::Done) as usize, assigned + 1)
+                    });
+                (assigned > 0).then(|| (member.name().to_string(), done as f64 / assigned as f64))
+            })
+            .collect()
+    }
+
+    /// Rebuild each task's member names from the members' own task lists, e.g. for project files
+    /// saved before tasks kept them
+    pub(crate) fn sync_members(&mut self, members: &Members) {
+        self.store.values_mut().for_each(|task| {
+            task.members = members
+                .members()
+                .filter(|member| member.is_assigned_to(&task.id))
+                .map(|member| member.name().to_string())
+                .collect();
+        });
+    }
+
+    /// Leaf tasks a member is assigned to, in id order
+    pub fn tasks_for_member<'a>(&'a self, name: &'a str) -> impl Iterator<Item=&'a Task> {
+        self.get_tasks()
+            .filter(move |task| task.has_member(name))
+    }
+
+    /// Total planned value of the leaf tasks a member is assigned to
+    pub fn member_workload(&self, name: &str) -> f64 {
+        self.tasks_for_member(name)
+            .map(|task| task.get_planned_value())
+            .sum()
+    }
+
+    /// Names of everyone assigned to at least one leaf task, sorted. Only leaves carry members,
+    /// so each name shows up once however many tasks it's on
+    pub fn all_members(&self) -> BTreeSet<String> {
+        self.get_tasks()
+            .flat_map(|task| task.members().map(str::to_string))
+            .collect()
+    }
+
+    /// Sorted ids of the leaf tasks assigned to each member, a shared task showing up under every
+    /// one of its members. Like in `member_completion`, members without assigned tasks are left
+    /// out, and so are tasks nobody is assigned to
+    pub fn partition_by_member(&self, members: &Members) -> HashMap<String, Vec<TaskId>> {
+        members
+            .members()
+            .filter_map(|member| {
+                let mut ids = member
+                    .task_ids()
+                    .filter(|id| self.get(id).is_ok_and(Self::is_leaf_task))
+                    .cloned()
+                    .collect::<Vec<TaskId>>();
+                ids.sort();
+                (!ids.is_empty()).then(|| (member.name().to_string(), ids))
+            })
+            .collect()
     }
 
     pub(crate) fn remove_task(&mut self, task_id: &TaskId) -> Result<Task, Error> {
-        self.store.remove(task_id)
-            .ok_or_else(|| Error::TaskNotFound(task_id.clone()))
+        let task = self.store.remove(task_id)
+            .ok_or_else(|| Error::TaskNotFound(task_id.clone()))?;
+        self.update_counts(&task, false);
+        Ok(task)
+    }
+
+    /// Whether a task is one of the leaf tasks the project is made of. The root of a project with
+    /// no tasks yet has no children, but it isn't one of them
+    pub(crate) fn is_leaf_task(task: &Task) -> bool {
+        task.is_leaf() && !task.id().as_vec().is_empty()
+    }
+
+    /// How much a task adds to the leaf and done counts. Like `get_done_tasks`, only leaves count
+    /// as done
+    fn counted(task: &Task) -> (usize, usize) {
+        let leaf = Self::is_leaf_task(task);
+        (leaf as usize, (leaf && task.status == TaskStatus::Done) as usize)
+    }
+
+    /// Account for a task entering (`added`) or leaving the store in the maintained counts
+    fn update_counts(&mut self, task: &Task, added: bool) {
+        let (leaf, done) = Self::counted(task);
+        if added {
+            self.leaf_count += leaf;
+            self.done_count += done;
+        } else {
+            self.leaf_count -= leaf;
+            self.done_count -= done;
+        }
+    }
+
+    /// Change a stored task in place, keeping the maintained counts in sync
+    fn update_task<F: FnOnce(&mut Task)>(&mut self, task_id: &TaskId, func: F) -> Result<(), Error> {
+        let task = self.store
+            .get_mut(task_id)
+            .ok_or_else(|| Error::TaskNotFound(task_id.clone()))?;
+        let (old_leaf, old_done) = Self::counted(task);
+        func(task);
+        let (leaf, done) = Self::counted(task);
+        self.leaf_count = self.leaf_count + leaf - old_leaf;
+        self.done_count = self.done_count + done - old_done;
+        Ok(())
+    }
+
+    /// Also stamps tasks becoming done with today's date, and clears it when they're reopened
+    fn set_status(&mut self, task_id: &TaskId, status: TaskStatus) -> Result<(), Error> {
+        self.update_task(task_id, |task| {
+            if status != TaskStatus::Done {
+                task.done_date = None;
+            } else if task.status != TaskStatus::Done {
+                task.done_date = Some(Utc::now().naive_utc().date());
+            }
+            task.status = status;
+        })
+    }
+
+    fn set_num_child(&mut self, task_id: &TaskId, num_child: u32) -> Result<(), Error> {
+        self.update_task(task_id, |task| task.num_child = num_child)
     }
 
     pub(crate) fn add_dependency(&mut self, task_id: &TaskId, dependency_id: &TaskId) -> Result<(), Error> {
+        if task_id == dependency_id {
+            return Err(Error::SelfDependency(task_id.clone()))
+        }
         if self.get_mut(task_id)?.is_trunk() {
             return Err(Error::TrunkCannotHaveDependency(task_id.clone()))
         }
         if self.get_mut(dependency_id)?.is_trunk() {
             return Err(Error::TrunkCannotBeDependency(dependency_id.clone()))
         }
+        if self.would_create_cycle(task_id, dependency_id) {
+            return Err(Error::DependencyCycle(task_id.clone(), dependency_id.clone()))
+        }
         // SAFETY: we already performed `get_mut`, so we know these exist
         self.store.get_mut(dependency_id).unwrap().dependency_for.insert(task_id.clone());
         self.store.get_mut(task_id).unwrap().dependencies.insert(dependency_id.clone());
-        if self.dependency_cycle_exists()? {
-            self.store.get_mut(task_id).unwrap().dependencies.remove(dependency_id);
-            self.store.get_mut(dependency_id).unwrap().dependency_for.remove(task_id);
-            return Err(Error::EdgeCreationLeadsToCycle(task_id.clone(), dependency_id.clone()))
+        self.refresh_blocked(task_id)
+    }
+
+    /// Make each task depend on the one before it in `ids`, laying them out serially. Either every
+    /// edge is added or, on error, none of them
+    pub(crate) fn chain_dependencies(&mut self, ids: &[TaskId]) -> Result<(), Error> {
+        ids.iter().try_for_each(|id| self.get(id).map(|_| ()))?;
+        let mut added: Vec<(&TaskId, &TaskId)> = Vec::new();
+        for pair in ids.windows(2) {
+            let (dependency_id, task_id) = (&pair[0], &pair[1]);
+            if self.get(task_id)?.dependencies.contains(dependency_id) {
+                continue;
+            }
+            if let Err(err) = self.add_dependency(task_id, dependency_id) {
+                added.iter().rev().for_each(|(task_id, dependency_id)| {
+                    self.remove_dependency(task_id, dependency_id).unwrap();
+                });
+                return Err(err);
+            }
+            added.push((task_id, dependency_id));
         }
         Ok(())
     }
 
+    /// Whether making `from` depend on `to` would close a cycle, i.e. whether `from` is already
+    /// reachable from `to` through dependencies
+    pub fn would_create_cycle(&self, from: &TaskId, to: &TaskId) -> bool {
+        self.depends_on(to, from)
+    }
+
+    /// Whether `dependency_id` can be reached from `task_id` by following dependencies, counting
+    /// `task_id` itself
+    fn depends_on(&self, task_id: &TaskId, dependency_id: &TaskId) -> bool {
+        let mut visited = HashSet::new();
+        let mut stack = vec![task_id];
+        while let Some(id) = stack.pop() {
+            if id == dependency_id {
+                return true;
+            }
+            if !visited.insert(id) {
+                continue;
+            }
+            if let Ok(task) = self.get(id) {
+                stack.extend(task.dependencies.iter());
+            }
+        }
+        false
+    }
+
+    /// Leaf tasks ordered so that each one comes after every task it depends on. Independent tasks
+    /// come in id order
+    pub fn topological_order(&self) -> Result<Vec<TaskId>, Error> {
+        let mut in_degree = self.get_tasks()
+            .map(|task| (task.id(), task.dependencies.len()))
+            .collect::<HashMap<&TaskId, usize>>();
+        let mut ready = in_degree
+            .iter()
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(id, _)| (*id).clone())
+            .collect::<BTreeSet<TaskId>>();
+        let mut order = Vec::with_capacity(in_degree.len());
+        while let Some(id) = ready.pop_first() {
+            for dependent_id in &self.get(&id)?.dependency_for {
+                if let Some(degree) = in_degree.get_mut(dependent_id) {
+                    *degree -= 1;
+                    if *degree == 0 {
+                        ready.insert(dependent_id.clone());
+                    }
+                }
+            }
+            order.push(id);
+        }
+        if order.len() != in_degree.len() {
+            return Err(self.cycle_error(&order));
+        }
+        Ok(order)
+    }
+
+    /// `DependencyCycle` with an edge on a cycle among the leaf tasks `topological_order` couldn't
+    /// place. Each of those depends on another one left out, so following the dependencies from
+    /// any of them has to come back to a task already seen
+    fn cycle_error(&self, order: &[TaskId]) -> Error {
+        let placed = order.iter().collect::<HashSet<&TaskId>>();
+        let mut seen = HashSet::new();
+        let mut next = self.get_tasks().map(|task| task.id()).filter(|id| !placed.contains(id)).min();
+        while let Some(id) = next {
+            let task = match self.get(id) {
+                Ok(task) => task,
+                Err(error) => return error,
+            };
+            seen.insert(id);
+            let dependency = task.dependencies
+                .iter()
+                .filter(|id| !placed.contains(id))
+                .min()
+                .expect("tasks left out of the order depend on another one left out");
+            if seen.contains(dependency) {
+                return Error::DependencyCycle(id.clone(), dependency.clone());
+            }
+            next = Some(dependency);
+        }
+        unreachable!("topological_order only fails when some task is left out")
+    }
+
+    /// Leaf tasks ordered so that each one comes before every task it depends on, i.e. the
+    /// reverse of `topological_order`
+    pub fn reverse_topological_order(&self) -> Result<Vec<TaskId>, Error> {
+        let mut order = self.topological_order()?;
+        order.reverse();
+        Ok(order)
+    }
+
+    /// Chain of dependent leaf tasks with the highest total planned value, from the first task to
+    /// the last. Ties go to the lowest ids
+    pub fn critical_path(&self) -> Result<Vec<TaskId>, Error> {
+        let order = self.topological_order()?;
+        // heaviest chain ending at each task, and the task before it in that chain
+        let mut chains: HashMap<&TaskId, (f64, Option<&TaskId>)> = HashMap::with_capacity(order.len());
+        for id in &order {
+            let task = self.get(id)?;
+            let mut dependencies = task.dependencies.iter().collect::<Vec<&TaskId>>();
+            dependencies.sort();
+            let (weight, previous) = dependencies
+                .into_iter()
+                .fold((0.0, None), |(weight, previous), dependency_id| {
+                    let (dependency_weight, _) = chains[dependency_id];
+                    if previous.is_none() || dependency_weight > weight {
+                        (dependency_weight, Some(dependency_id))
+                    } else {
+                        (weight, previous)
+                    }
+                });
+            chains.insert(id, (weight + task.planned_value, previous));
+        }
+
+        let mut last = None;
+        for id in &order {
+            if last.is_none_or(|(weight, _)| chains[id].0 > weight) {
+                last = Some((chains[id].0, id));
+            }
+        }
+        let mut path = Vec::new();
+        let mut current = last.map(|(_, id)| id);
+        while let Some(id) = current {
+            path.push(id.clone());
+            current = chains[id].1;
+        }
+        path.reverse();
+        Ok(path)
+    }
+
+    /// Number of edges in the longest chain of dependencies between leaf tasks, 0 when there are
+    /// no dependencies. The longer it is, the less work can be done in parallel
+    pub fn max_dependency_depth(&self) -> Result<usize, Error> {
+        let order = self.topological_order()?;
+        let mut depths: HashMap<&TaskId, usize> = HashMap::with_capacity(order.len());
+        for id in &order {
+            let depth = self.get(id)?.dependencies
+                .iter()
+                .map(|dependency_id| depths[dependency_id] + 1)
+                .max()
+                .unwrap_or(0);
+            depths.insert(id, depth);
+        }
+        Ok(depths.into_values().max().unwrap_or(0))
+    }
+
+    /// Total float of every leaf task: how much its planned value (taken as its duration, as in
+    /// `critical_path`) could grow before delaying the end of the project. Tasks on the critical
+    /// path have none
+    pub fn total_floats(&self) -> Result<HashMap<TaskId, f64>, Error> {
+        let order = self.topological_order()?;
+        let mut earliest_finish: HashMap<&TaskId, f64> = HashMap::with_capacity(order.len());
+        for id in &order {
+            let task = self.get(id)?;
+            let start = task.dependencies
+                .iter()
+                .map(|dependency_id| earliest_finish[dependency_id])
+                .fold(0.0, f64::max);
+            earliest_finish.insert(id, start + task.planned_value);
+        }
+        let end = earliest_finish.values().copied().fold(0.0, f64::max);
+
+        let mut latest_finish: HashMap<&TaskId, f64> = HashMap::with_capacity(order.len());
+        for id in order.iter().rev() {
+            let finish = self.get(id)?.dependency_for
+                .iter()
+                .map(|dependent_id| latest_finish[dependent_id] - self.get(dependent_id).unwrap().planned_value)
+                .fold(end, f64::min);
+            latest_finish.insert(id, finish);
+        }
+        Ok(order
+            .iter()
+            .map(|id| (id.clone(), latest_finish[id] - earliest_finish[id]))
+            .collect())
+    }
+
+    /// Not done leaf tasks with their total float, the most constrained first (ties in id order)
+    pub fn slack_report(&self) -> Result<Vec<(TaskId, f64)>, Error> {
+        let mut report = self.total_floats()?
+            .into_iter()
+            .filter(|(id, _)| self.get(id).is_ok_and(|task| task.status != TaskStatus::Done))
+            .collect::<Vec<(TaskId, f64)>>();
+        report.sort_by(|(a_id, a_float), (b_id, b_float)| a_float.total_cmp(b_float).then_with(|| a_id.cmp(b_id)));
+        Ok(report)
+    }
+
+    /// Drop every dependency already implied through another dependency (the transitive
+    /// reduction of the dependency graph), returning how many were dropped
+    pub(crate) fn minimize_dependencies(&mut self) -> Result<usize, Error> {
+        let redundant = self.store
+            .values()
+            .flat_map(|task| task.dependencies.iter().map(move |dependency_id| (task, dependency_id)))
+            .filter(|(task, dependency_id)| {
+                task.dependencies
+                    .iter()
+                    .any(|other_id| other_id != *dependency_id && self.depends_on(other_id, dependency_id))
+            })
+            .map(|(task, dependency_id)| (task.id.clone(), dependency_id.clone()))
+            .collect::<Vec<(TaskId, TaskId)>>();
+        redundant
+            .iter()
+            .try_for_each(|(task_id, dependency_id)| self.remove_dependency(task_id, dependency_id))?;
+        Ok(redundant.len())
+    }
+
     pub(crate) fn remove_dependency(&mut self, task_id: &TaskId, dependency_id: &TaskId) -> Result<(), Error> {
         if !self.get_mut(task_id)?.dependencies.contains(dependency_id) {
             return Err(Error::TaskNotFound(dependency_id.clone()));
@@ -117,7 +859,58 @@ impl Tasks {
         self.get_mut(task_id).unwrap().dependencies.remove(dependency_id);
         self.get_mut(dependency_id).unwrap().dependency_for.remove(task_id);
 
-        Ok(())
+        self.refresh_blocked(task_id)
+    }
+
+    /// Sorted ids of the tasks a task depends on, or `None` if there's no such task
+    pub fn dependencies_of(&self, task_id: &TaskId) -> Option<Vec<TaskId>> {
+        let mut dependencies = self.get(task_id)
+            .ok()?
+            .dependencies
+            .iter()
+            .cloned()
+            .collect::<Vec<TaskId>>();
+        dependencies.sort();
+        Some(dependencies)
+    }
+
+    /// Status of a leaf task that isn't done: blocked while any of its dependencies isn't done
+    fn open_status(&self, task: &Task) -> TaskStatus {
+        let blocked = task.dependencies
+            .iter()
+            .any(|id| self.get(id).map_or(true, |dependency| dependency.status != TaskStatus::Done));
+        if blocked {
+            TaskStatus::Blocked
+        } else {
+            TaskStatus::InProgress
+        }
+    }
+
+    /// Mark a leaf task that isn't done as blocked or in progress, according to its dependencies
+    fn refresh_blocked(&mut self, task_id: &TaskId) -> Result<(), Error> {
+        let task = self.get(task_id)?;
+        if task.is_trunk() || task.status == TaskStatus::Done {
+            return Ok(());
+        }
+        let status = self.open_status(task);
+        self.set_status(task_id, status)
+    }
+
+    /// `refresh_blocked` every task with dependencies
+    fn refresh_blocked_statuses(&mut self) -> Result<(), Error> {
+        let ids = self.store
+            .values()
+            .filter(|task| !task.dependencies.is_empty())
+            .map(|task| task.id.clone())
+            .collect::<Vec<TaskId>>();
+        ids.iter().try_for_each(|id| self.refresh_blocked(id))
+    }
+
+    /// Whether `task_id` is somewhere in the subtree under `ancestor_id` (not counting itself)
+    pub fn is_under(&self, task_id: &TaskId, ancestor_id: &TaskId) -> Result<bool, Error> {
+        self.get(task_id)?;
+        self.get(ancestor_id)?;
+        Ok(task_id.is_descendant_of(ancestor_id))
     }
 
     pub fn next_sibling(&self, task_id: &TaskId) -> Result<&Task, Error> {
@@ -134,7 +927,7 @@ impl Tasks {
 
     pub(crate) fn add_task(&mut self, parent_task_id: TaskId, name: &str) -> Result<&mut Task, Error> {
         // get parent
-        let parent_task = self.get_mut(&parent_task_id)?;
+        let parent_task = self.get(&parent_task_id)?;
 
         // trunks can't be or have dependencies, so we need to check
         if !parent_task.dependencies.is_empty() || !parent_task.dependency_for.is_empty() {
@@ -142,10 +935,11 @@ impl Tasks {
         }
 
         // increase number of children
-        parent_task.num_child += 1;
+        let num_child = parent_task.num_child + 1;
+        self.set_num_child(&parent_task_id, num_child)?;
 
         // get new task id
-        let task_id = parent_task_id.new_child_id(parent_task.num_child)?;
+        let task_id = parent_task_id.new_child_id(num_child)?;
 
         // create task
         let task = Task::new(task_id.clone(), name);
@@ -154,9 +948,9 @@ impl Tasks {
         self.insert(task_id.clone(), task);
 
         // since new tasks are always not done, all parents must be not done too
-        self.apply_along_path(&task_id, |task| {
-            task.status = TaskStatus::InProgress;
-        })?;
+        task_id
+            .path()
+            .try_for_each(|id| self.set_status(&id, TaskStatus::InProgress))?;
 
         self.get_mut(&task_id)
     }
@@ -168,74 +962,559 @@ impl Tasks {
         Ok(self)
     }
 
-    fn apply_along_path<F: Fn(&mut Task)>(&mut self, id: &TaskId, func: F) -> Result<(), Error> {
-        id
-            .path()
-            .try_for_each(|id| {
-                let child = self.get_mut(&id)?;
-                func(child);
-                Ok(())
-            })
+    /// Build a fresh task tree from a template. Planned values are set once every task exists, so
+    /// entries that end up as trunks must have a planned value of 0
+    pub(crate) fn from_template(template: &Template) -> Result<Self, Error> {
+        let mut tasks = Self::with_capacity(template.name(), template.entries().count() + 1);
+        let ids = template
+            .entries()
+            .map(|entry| Ok(tasks.add_task(TaskId::parse(entry.parent())?, entry.name())?.id().clone()))
+            .collect::<Result<Vec<TaskId>, Error>>()?;
+        ids
+            .iter()
+            .zip(template.entries())
+            .filter(|(_, entry)| entry.planned_value() != 0.0)
+            .try_for_each(|(id, entry)| tasks.set_planned_value(id, entry.planned_value()).map(|_| ()))?;
+        Ok(tasks)
     }
 
-    fn subtract_id(&mut self, child_id: &TaskId, layer_idx: usize) -> Result<(), Error> {
-        let num_child = self.get(child_id)?.num_child;
-        let old_task_id = child_id.clone();
-        let mut new_task_id = child_id.clone();
-        new_task_id.as_vec_mut()[layer_idx] -= 1;
-        let mut task = self.remove_task(&old_task_id)?;
-        task.id = new_task_id.clone();
-        self.insert(
-            new_task_id,
-            task
-        );
-
-        child_id.child_ids(num_child).try_for_each(|node_id| {
-            self.subtract_id(&node_id, layer_idx)
-        })
+    /// Set the status of each listed leaf task, rolling the statuses up the tree only once
+    /// afterwards. Like everywhere else, whether a task that isn't done is blocked comes from its
+    /// dependencies, not from the update. Returns the ids that couldn't be applied, either because
+    /// they don't exist or because they are trunks
+    pub(crate) fn apply_status_updates(&mut self, updates: &HashMap<TaskId, TaskStatus>) -> Result<Vec<TaskId>, Error> {
+        let mut skipped = Vec::new();
+        for (id, status) in updates {
+            if self.get(id).is_ok_and(Self::is_leaf_task) {
+                let status = match status {
+                    TaskStatus::Done => TaskStatus::Done,
+                    _ => self.open_status(self.get(id)?),
+                };
+                self.set_status(id, status)?;
+            } else {
+                skipped.push(id.clone());
+            }
+        }
+        self.refresh_blocked_statuses()?;
+        self.roll_up_statuses();
+        skipped.sort();
+        Ok(skipped)
     }
 
-    pub(crate) fn remove(&mut self, task_id: &TaskId, members: &Members) -> Result<Task, Error> {
-        // don't remove if this is a trunk node
-        if self.get(task_id)?.num_child > 0 {
-            return Err(Error::TrunkCannotBeRemoved(task_id.clone()));
-        }
-        // task can't be removed if there are members assigned to it
-        if members.members().any(|member| member.is_assigned_to(task_id)) {
-            return Err(Error::CannotRemoveAssignedTask(task_id.clone()))
-        }
-        // task can't be removed if has or is a dependency
-        if !self.get(task_id)?.dependencies.is_empty() || !self.get(task_id)?.dependency_for.is_empty() {
-            return Err(Error::CannotRemoveWithDependency(task_id.clone()))
+    /// Set the actual cost of many leaf tasks at once, e.g. from a time tracker, marking them done
+    /// as `set_actual_cost` does. Trunk totals and statuses are rolled up once at the end. Returns
+    /// the sorted ids that were skipped, either unknown or trunks
+    pub(crate) fn apply_actual_costs(&mut self, costs: &HashMap<TaskId, f64>) -> Result<Vec<TaskId>, Error> {
+        let mut skipped = Vec::new();
+        for (id, actual_cost) in costs {
+            if self.get(id).is_ok_and(Self::is_leaf_task) {
+                self.get_mut(id)?.actual_cost = *actual_cost;
+                self.set_status(id, TaskStatus::Done)?;
+            } else {
+                skipped.push(id.clone());
+            }
         }
+        self.recompute_aggregates();
+        self.refresh_blocked_statuses()?;
+        self.roll_up_statuses();
+        skipped.sort();
+        Ok(skipped)
+    }
 
-        self.remove_task_stats_from_tree(task_id)?;
+    /// Recompute every trunk status from its children, bottom-up
+    fn roll_up_statuses(&mut self) {
+        self.dfs_ids()
+            .iter()
+            .rev()
+            .for_each(|id| {
+                if self.get(id).unwrap().is_leaf() {
+                    return;
+                }
+                let status = if self.children_are_done(id) {
+                    TaskStatus::Done
+                } else {
+                    TaskStatus::InProgress
+                };
+                self.set_status(id, status).unwrap();
+            });
+    }
 
-        let parent_id = task_id.parent()?;
-        let parent_childs: _ = {
-            let mut parent = self.get_mut(&parent_id)?;
-            parent.num_child -= 1;
-            parent.id()
-                .child_ids(parent.num_child+1)
-                .collect::<Vec<TaskId>>()
-        };
+    /// Give a task a new name. Renaming the root renames the project
+    pub(crate) fn rename_task(&mut self, task_id: &TaskId, new_name: &str) -> Result<(), Error> {
+        let task = self.get_mut(task_id)?;
+        if new_name.trim().is_empty() {
+            return Err(Error::EmptyTaskName(task_id.clone()));
+        }
+        task.name = new_name.to_string();
+        Ok(())
+    }
 
-        let layer_idx = task_id.len() - 1;
-        let child_idx = task_id.child_idx()? as usize - 1;
+    /// Rename several tasks at once, returning how many names actually changed. Nothing is renamed
+    /// if any id is unknown or any new name is blank, the error being about the lowest such id
+    pub(crate) fn rename_many(&mut self, renames: &HashMap<TaskId, String>) -> Result<usize, Error> {
+        let mut ids = renames.keys().collect::<Vec<&TaskId>>();
+        ids.sort();
+        for id in &ids {
+            self.get(id)?;
+            if renames[*id].trim().is_empty() {
+                return Err(Error::EmptyTaskName((*id).clone()));
+            }
+        }
+        Ok(ids
+            .into_iter()
+            .filter(|id| {
+                let task = self.store.get_mut(id).unwrap();
+                let changed = task.name != renames[*id];
+                task.name = renames[*id].clone();
+                changed
+            })
+            .count())
+    }
+
+    pub(crate) fn set_description(&mut self, task_id: &TaskId, description: Option<&str>) -> Result<(), Error> {
+        self.get_mut(task_id)?.description = description.map(str::to_string);
+        Ok(())
+    }
+
+    pub(crate) fn set_start_date(&mut self, task_id: &TaskId, date: Option<NaiveDate>) -> Result<(), Error> {
+        self.get_mut(task_id)?.start_date = date;
+        Ok(())
+    }
+
+    pub(crate) fn set_due_date(&mut self, task_id: &TaskId, date: Option<NaiveDate>) -> Result<(), Error> {
+        self.get_mut(task_id)?.due_date = date;
+        Ok(())
+    }
+
+    /// Cumulative planned value of the leaf tasks due by each distinct due date, in date order, for
+    /// plotting an S-curve. Tasks without a due date are left out of it and added up separately,
+    /// as the second value
+    pub fn planned_value_curve(&self) -> (Vec<(NaiveDate, f64)>, f64) {
+        let mut by_date = BTreeMap::new();
+        let mut unscheduled = 0.0;
+        self.get_tasks()
+            .for_each(|task| match task.due_date {
+                Some(due_date) => *by_date.entry(due_date).or_insert(0.0) += task.planned_value,
+                None => unscheduled += task.planned_value,
+            });
+        let mut total = 0.0;
+        let curve = by_date
+            .into_iter()
+            .map(|(date, planned_value)| {
+                total += planned_value;
+                (date, total)
+            })
+            .collect();
+        (curve, unscheduled)
+    }
+
+    /// Copy of the tree cut at `max_depth`: tasks at that depth become leaves carrying the rolled-up
+    /// planned value, actual cost and status of everything that was under them. Dependencies on
+    /// tasks that were cut away are dropped
+    pub fn aggregate_to_depth(&self, max_depth: usize) -> Tasks {
+        let mut store = self.store.empty_like();
+        self.store
+            .iter()
+            .filter(|(id, _)| id.len() <= max_depth)
+            .for_each(|(id, task)| { store.insert(id.clone(), task.clone()); });
+        let kept = store.keys().cloned().collect::<HashSet<TaskId>>();
+        store.values_mut().for_each(|task| {
+            if task.id.len() == max_depth {
+                task.num_child = 0;
+            }
+            task.dependencies.retain(|id| kept.contains(id));
+            task.dependency_for.retain(|id| kept.contains(id));
+        });
+        let mut tasks = Tasks {
+            store,
+            ordered: self.ordered,
+            leaf_count: 0,
+            done_count: 0,
+            earned_value_mode: self.earned_value_mode,
+            target_planned_value: self.target_planned_value,
+        };
+        tasks.recount();
+        tasks
+    }
+
+    /// Backdate when a task was done, e.g. when recording work after the fact
+    pub(crate) fn set_done_date(&mut self, task_id: &TaskId, date: NaiveDate) -> Result<(), Error> {
+        let task = self.get_mut(task_id)?;
+        if task.status != TaskStatus::Done {
+            return Err(Error::TaskNotDone(task_id.clone()));
+        }
+        task.done_date = Some(date);
+        Ok(())
+    }
+
+    /// Planned value of a task spread evenly over each day from its start to its due date, both
+    /// included
+    pub fn phase_budget(&self, task_id: &TaskId) -> Result<Vec<(NaiveDate, f64)>, Error> {
+        let task = self.get(task_id)?;
+        let (start_date, due_date) = task.start_date
+            .zip(task.due_date)
+            .ok_or_else(|| Error::MissingDates(task_id.clone()))?;
+        if due_date < start_date {
+            return Err(Error::InvalidDateRange(task_id.clone()));
+        }
+        let days = start_date.iter_days().take_while(|date| *date <= due_date).collect::<Vec<NaiveDate>>();
+        let daily_value = task.planned_value / days.len() as f64;
+        Ok(days.into_iter().map(|date| (date, daily_value)).collect())
+    }
+
+    /// Multiply the planned value and actual cost of every task by `factor`, e.g. to convert
+    /// currencies. Roll-ups scale linearly, so they stay consistent
+    pub(crate) fn scale_values(&mut self, factor: f64) -> Result<(), Error> {
+        if !factor.is_finite() || factor < 0.0 {
+            return Err(Error::InvalidScaleFactor);
+        }
+        self.store.values_mut().for_each(|task| {
+            task.planned_value *= factor;
+            task.actual_cost *= factor;
+        });
+        Ok(())
+    }
+
+    /// Estimate leaf tasks from a similar past project: leaves named like a leaf in `reference` get
+    /// its planned value times `scale`. When the reference repeats a name, its first leaf in
+    /// pre-order is used. Unmatched leaves keep their planned value
+    pub(crate) fn apply_analogous_estimate(&mut self, reference: &Tasks, scale: f64) -> Result<(), Error> {
+        if !scale.is_finite() || scale < 0.0 {
+            return Err(Error::InvalidScaleFactor);
+        }
+        let mut reference_values = HashMap::new();
+        reference
+            .dfs_ids()
+            .iter()
+            .filter_map(|id| reference.get(id).ok())
+            .filter(|task| Self::is_leaf_task(task))
+            .for_each(|task| {
+                reference_values.entry(task.name()).or_insert(task.planned_value);
+            });
+        let estimates = self.store
+            .values()
+            .filter(|task| Self::is_leaf_task(task))
+            .filter_map(|task| reference_values
+                .get(task.name())
+                .map(|planned_value| (task.id.clone(), planned_value * scale)))
+            .collect::<Vec<(TaskId, f64)>>();
+        estimates
+            .iter()
+            .try_for_each(|(id, planned_value)| self.set_planned_value(id, *planned_value).map(|_| ()))
+    }
+
+    /// Apply `func` to a task and all its ancestors. Nothing is changed if any of them is missing
+    fn apply_along_path<F: Fn(&mut Task)>(&mut self, id: &TaskId, func: F) -> Result<(), Error> {
+        id.path().try_for_each(|id| self.get(&id).map(|_| ()))?;
+        id
+            .path()
+            .try_for_each(|id| {
+                let child = self.get_mut(&id)?;
+                func(child);
+                Ok(())
+            })
+    }
+
+    /// Move tasks to new ids, rewriting the dependency references to them
+    fn remap_ids(&mut self, mapping: &HashMap<TaskId, TaskId>) {
+        self.move_tasks(mapping);
+        self.remap_dependencies(mapping);
+    }
+
+    /// Move tasks to new ids, leaving the references to them as they are. Every task is taken out
+    /// before any is reinserted, so the mapping may reuse ids that other tasks are moving away from
+    fn move_tasks(&mut self, mapping: &HashMap<TaskId, TaskId>) {
+        let moved = mapping
+            .keys()
+            .filter_map(|id| self.remove_task(id).ok())
+            .collect::<Vec<Task>>();
+        moved.into_iter().for_each(|mut task| {
+            task.id = mapping[task.id()].clone();
+            self.insert(task.id.clone(), task);
+        });
+    }
+
+    /// Point the `dependencies` and `dependency_for` entries of every task at the new ids of the
+    /// moved ones
+    fn remap_dependencies(&mut self, mapping: &HashMap<TaskId, TaskId>) {
+        let remap = |id: &TaskId| mapping.get(id).unwrap_or(id).clone();
+        self.store.values_mut().for_each(|task| {
+            task.dependencies = task.dependencies.iter().map(remap).collect();
+            task.dependency_for = task.dependency_for.iter().map(remap).collect();
+        });
+    }
+
+    /// Move a task and its subtree under another parent. With `keep_index` the task keeps its
+    /// child index if that position exists under the new parent, shifting the siblings after it,
+    /// otherwise it's appended as the last child. Member assignments follow the renumbered tasks.
+    /// Returns the new id of the task
+    pub(crate) fn reparent(&mut self, task_id: &TaskId, new_parent_id: &TaskId, keep_index: bool, members: &mut Members) -> Result<TaskId, Error> {
+        let old_parent_id = task_id.parent()?;
+        let child_idx = task_id.child_idx()?;
+        let (planned_value, actual_cost) = {
+            let task = self.get(task_id)?;
+            (task.planned_value, task.actual_cost)
+        };
+        let new_parent = self.get(new_parent_id)?;
+        if new_parent_id == task_id || new_parent_id.is_descendant_of(task_id) {
+            return Err(Error::CannotMoveIntoDescendant(task_id.clone(), new_parent_id.clone()));
+        }
+        // trunks can't be or have dependencies, so we need to check
+        if !new_parent.dependencies.is_empty() || !new_parent.dependency_for.is_empty() {
+            return Err(Error::CannotConvertToTrunk(new_parent_id.clone()));
+        }
+        if old_parent_id == *new_parent_id {
+            return Ok(task_id.clone());
+        }
+        let new_idx = if keep_index && child_idx <= new_parent.num_child {
+            child_idx
+        } else {
+            new_parent.num_child + 1
+        };
+
+        self.apply_along_path(&old_parent_id, |task| {
+            task.planned_value -= planned_value;
+            task.actual_cost -= actual_cost;
+        })?;
+
+        // siblings after the task close the gap it leaves
+        let old_layer = old_parent_id.len();
+        let close_gap = |id: &TaskId| {
+            let mut id = id.clone();
+            if old_parent_id.is_ancestor_of(&id) && id.as_vec()[old_layer] > child_idx {
+                id.as_vec_mut()[old_layer] -= 1;
+            }
+            id
+        };
+        // children of the new parent from `new_idx` onwards make room for it
+        let new_parent_id = close_gap(new_parent_id);
+        let new_layer = new_parent_id.len();
+        let renumber = |id: &TaskId| {
+            if id == task_id || id.is_descendant_of(task_id) {
+                let suffix = &id.as_vec()[task_id.len()..];
+                return TaskId::new([new_parent_id.as_vec().as_slice(), &[new_idx], suffix].concat());
+            }
+            let mut id = close_gap(id);
+            if new_parent_id.is_ancestor_of(&id) && id.as_vec()[new_layer] >= new_idx {
+                id.as_vec_mut()[new_layer] += 1;
+            }
+            id
+        };
+        let mapping = self.store
+            .keys()
+            .map(|id| (id.clone(), renumber(id)))
+            .filter(|(old_id, new_id)| old_id != new_id)
+            .collect::<HashMap<TaskId, TaskId>>();
+        let old_parent_id = renumber(&old_parent_id);
+        self.remap_ids(&mapping);
+        members.remap_task_ids(&mapping);
+
+        let old_parent_num_child = self.get(&old_parent_id)?.num_child;
+        self.set_num_child(&old_parent_id, old_parent_num_child - 1)?;
+        let new_parent_num_child = self.get(&new_parent_id)?.num_child;
+        self.set_num_child(&new_parent_id, new_parent_num_child + 1)?;
+        self.apply_along_path(&new_parent_id, |task| {
+            task.planned_value += planned_value;
+            task.actual_cost += actual_cost;
+        })?;
+        self.roll_up_statuses();
+
+        new_parent_id.new_child_id(new_idx)
+    }
+
+    /// Renumber tasks to the ids of their namesakes in `other`. Children are only matched when both
+    /// sides have the same distinct child names, otherwise that level keeps its own numbering and
+    /// its subtree isn't aligned any further. Member assignments follow the renumbered tasks.
+    /// Returns the mapping from old to new ids of the tasks that moved
+    pub(crate) fn align_ids_to(&mut self, other: &Tasks, members: &mut Members) -> Result<HashMap<TaskId, TaskId>, Error> {
+        let root_id = TaskId::get_root_id();
+        let mut mapping = HashMap::new();
+        self.align_subtree_ids(&root_id, &root_id, Some(&root_id), other, &mut mapping)?;
+        self.remap_ids(&mapping);
+        members.remap_task_ids(&mapping);
+        Ok(mapping)
+    }
+
+    fn align_subtree_ids(&self, task_id: &TaskId, new_task_id: &TaskId, other_id: Option<&TaskId>, other: &Tasks, mapping: &mut HashMap<TaskId, TaskId>) -> Result<(), Error> {
+        let task = self.get(task_id)?;
+        let names = task
+            .child_ids()
+            .map(|id| self.get(&id).map(|child| child.name()))
+            .collect::<Result<HashSet<&str>, Error>>()?;
+        let other_children = other_id
+            .and_then(|id| other.get(id).ok())
+            .map(|other_task| other_task
+                .child_ids()
+                .filter_map(|id| other.get(&id).ok())
+                .map(|child| (child.name(), child.id()))
+                .collect::<HashMap<&str, &TaskId>>())
+            .filter(|other_children| {
+                names.len() == task.num_child as usize &&
+                other_children.len() == names.len() &&
+                names.iter().all(|name| other_children.contains_key(name))
+            })
+            .unwrap_or_default();
+
+        for child_id in task.child_ids() {
+            let other_child_id = other_children.get(self.get(&child_id)?.name()).copied();
+            let child_idx = match other_child_id {
+                Some(id) => id.child_idx()?,
+                None => child_id.child_idx()?,
+            };
+            let new_child_id = new_task_id.new_child_id(child_idx)?;
+            self.align_subtree_ids(&child_id, &new_child_id, other_child_id, other, mapping)?;
+            if new_child_id != child_id {
+                mapping.insert(child_id, new_child_id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Move a task and its subtree one index back, into the gap left by a removed sibling. Returns
+    /// the mapping from old to new ids, for the references to them to be updated
+    fn subtract_id(&mut self, child_id: &TaskId) -> Result<HashMap<TaskId, TaskId>, Error> {
+        let new_task_id = child_id.with_last_decremented()?;
+        let mut subtree_ids = vec![];
+        self.subtree_dfs_ids(child_id, &mut subtree_ids);
+        let mapping = subtree_ids
+            .into_iter()
+            .map(|id| {
+                let suffix = &id.as_vec()[child_id.len()..];
+                let new_id = TaskId::new([new_task_id.as_vec().as_slice(), suffix].concat());
+                (id, new_id)
+            })
+            .collect::<HashMap<TaskId, TaskId>>();
+        self.move_tasks(&mapping);
+        Ok(mapping)
+    }
+
+    /// Member assignments follow the later siblings as they get renumbered
+    pub(crate) fn remove(&mut self, task_id: &TaskId, members: &mut Members) -> Result<Task, Error> {
+        // don't remove if this is a trunk node
+        if self.get(task_id)?.num_child > 0 {
+            return Err(Error::TrunkCannotBeRemoved(task_id.clone()));
+        }
+        // task can't be removed if there are members assigned to it
+        if members.members().any(|member| member.is_assigned_to(task_id)) {
+            return Err(Error::CannotRemoveAssignedTask(task_id.clone()))
+        }
+        self.detach_dependencies(task_id)?;
+        self.remove_task_stats_from_tree(task_id)?;
+
+        let parent_id = task_id.parent()?;
+        let parent_childs: _ = {
+            let num_child = self.get(&parent_id)?.num_child;
+            self.set_num_child(&parent_id, num_child - 1)?;
+            parent_id
+                .child_ids(num_child)
+                .collect::<Vec<TaskId>>()
+        };
+
+        let child_idx = task_id.child_idx()? as usize - 1;
 
         let task = self.remove_task(&task_id)?;
 
         // change id of child that comes after id node
+        let mut mapping = HashMap::new();
         parent_childs.iter().enumerate().try_for_each(|(index, child_id)| -> Result<(), _> {
             if child_idx < index {
-                self.subtract_id(&child_id, layer_idx)?;
+                mapping.extend(self.subtract_id(child_id)?);
             }
             Ok(())
         })?;
+        // then the edges onto the shifted tasks, so they keep pointing at the same tasks
+        self.remap_dependencies(&mapping);
+        members.remap_task_ids(&mapping);
 
         Ok(task)
     }
 
+    /// Remove a task along with everything under it, deepest tasks first, so only the task's later
+    /// siblings get renumbered. Dependency edges touching any removed task are dropped and the rest
+    /// follow the renumbering, as do member assignments. Nothing is removed if a member is assigned
+    /// to any of them. Returns the removed tasks in pre-order
+    pub(crate) fn remove_subtree(&mut self, task_id: &TaskId, members: &mut Members) -> Result<Vec<Task>, Error> {
+        self.get(task_id)?;
+        let mut ids = Vec::new();
+        self.subtree_dfs_ids(task_id, &mut ids);
+        if let Some(id) = ids.iter().find(|id| members.members().any(|member| member.is_assigned_to(id))) {
+            return Err(Error::CannotRemoveAssignedTask(id.clone()));
+        }
+        let mut removed = ids
+            .iter()
+            .rev()
+            .map(|id| self.remove(id, members))
+            .collect::<Result<Vec<Task>, Error>>()?;
+        removed.reverse();
+        Ok(removed)
+    }
+
+    /// Remove several leaf tasks in one go. The ids all refer to the tree as it is before any removal:
+    /// the final id of every remaining task is worked out first and applied in a single remap, so
+    /// no renumbering step can clobber a task another one hasn't moved yet. Nothing is removed if
+    /// any of them is missing, a trunk or assigned to a member. Member assignments follow the
+    /// renumbered tasks. Returns the mapping from old to new ids of the tasks that moved
+    pub(crate) fn renumber_batch(&mut self, removed: &[TaskId], members: &mut Members) -> Result<HashMap<TaskId, TaskId>, Error> {
+        let removed = removed.iter().cloned().collect::<BTreeSet<TaskId>>();
+        for id in removed.iter() {
+            id.parent()?;
+            if self.get(id)?.is_trunk() {
+                return Err(Error::TrunkCannotBeRemoved(id.clone()));
+            }
+            if members.members().any(|member| member.is_assigned_to(id)) {
+                return Err(Error::CannotRemoveAssignedTask(id.clone()));
+            }
+        }
+
+        // roll-ups walk the children by index, so they all go before any task is taken out
+        for id in removed.iter() {
+            self.detach_dependencies(id)?;
+            self.remove_task_stats_from_tree(id)?;
+        }
+        for id in removed.iter() {
+            let parent_id = id.parent()?;
+            let num_child = self.get(&parent_id)?.num_child;
+            self.set_num_child(&parent_id, num_child - 1)?;
+            self.remove_task(id)?;
+        }
+
+        // every level of an id moves back by the removed siblings before it at that level
+        let renumber = |id: &TaskId| {
+            let vec = id.as_vec();
+            (0..vec.len())
+                .map(|layer| {
+                    let shift = removed
+                        .iter()
+                        .filter(|removed_id| {
+                            removed_id.len() == layer + 1 &&
+                            removed_id.as_vec()[..layer] == vec[..layer] &&
+                            removed_id.as_vec()[layer] < vec[layer]
+                        })
+                        .count() as u32;
+                    vec[layer] - shift
+                })
+                .collect::<TaskId>()
+        };
+        let mapping = self.store
+            .keys()
+            .map(|id| (id.clone(), renumber(id)))
+            .filter(|(old_id, new_id)| old_id != new_id)
+            .collect::<HashMap<TaskId, TaskId>>();
+        self.remap_ids(&mapping);
+        members.remap_task_ids(&mapping);
+        self.roll_up_statuses();
+        Ok(mapping)
+    }
+
+    /// Remove every dependency edge touching a task, from both sides
+    fn detach_dependencies(&mut self, task_id: &TaskId) -> Result<(), Error> {
+        let task = self.get(task_id)?;
+        let dependencies = task.dependencies.iter().cloned().collect::<Vec<TaskId>>();
+        let dependents = task.dependency_for.iter().cloned().collect::<Vec<TaskId>>();
+        dependencies.iter().try_for_each(|id| self.remove_dependency(task_id, id))?;
+        dependents.iter().try_for_each(|id| self.remove_dependency(id, task_id))
+    }
+
     fn remove_task_stats_from_tree(&mut self, task_id: &TaskId) -> Result<(), Error> {
 
         self.set_actual_cost(&task_id, 0.0)?;
@@ -258,6 +1537,11 @@ impl Tasks {
                 return Err(Error::TrunkCannotChangeCost(task_id.clone()));
             }
             let old_actual_cost = task.actual_cost;
+            // already done at this cost, so neither the roll-up nor any status would change. A
+            // task not done yet still has to be marked done, even at the same cost
+            if old_actual_cost == actual_cost && task.status == TaskStatus::Done {
+                return Ok(());
+            }
             task.actual_cost = actual_cost;
             let diff = actual_cost - old_actual_cost;
 
@@ -265,20 +1549,87 @@ impl Tasks {
                     task.actual_cost += diff;
                 })?;
         }
+        self.promote_done(task_id)
+    }
+
+    /// Mark a leaf task done without touching its actual cost
+    pub(crate) fn mark_done(&mut self, task_id: &TaskId) -> Result<(), Error> {
+        if !Self::is_leaf_task(self.get(task_id)?) {
+            return Err(Error::CannotMarkTrunk(task_id.clone()));
+        }
+        self.set_status(task_id, TaskStatus::Done)?;
+        self.promote_done(task_id)
+    }
+
+    /// Set how far along a leaf task is, clamped between 0 and 1. Reaching 1 marks it done, while
+    /// anything less takes it back from done
+    pub(crate) fn set_progress(&mut self, task_id: &TaskId, progress: f64) -> Result<(), Error> {
+        let task = self.get_mut(task_id)?;
+        if !Self::is_leaf_task(task) {
+            return Err(Error::CannotMarkTrunk(task_id.clone()));
+        }
+        task.progress = if progress.is_nan() { 0.0 } else { progress.clamp(0.0, 1.0) };
+        if task.progress >= 1.0 {
+            self.mark_done(task_id)
+        } else if task.status == TaskStatus::Done {
+            self.reopen(task_id)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Take a leaf task back from done. It goes back to in progress, or blocked if a dependency
+    /// isn't done, and every ancestor is in progress again. Tasks depending on it get blocked. A
+    /// task done by reaching full progress starts over from none
+    pub(crate) fn reopen(&mut self, task_id: &TaskId) -> Result<(), Error> {
+        let task = self.get(task_id)?;
+        if !Self::is_leaf_task(task) {
+            return Err(Error::CannotMarkTrunk(task_id.clone()));
+        }
+        let status = self.open_status(task);
+        self.set_status(task_id, status)?;
+        let task = self.get_mut(task_id)?;
+        if task.progress >= 1.0 {
+            task.progress = 0.0;
+        }
+        task_id
+            .path()
+            .filter(|id| id != task_id)
+            .try_for_each(|id| self.set_status(&id, TaskStatus::InProgress))?;
+
+        let dependents = self.get(task_id)?
+            .dependency_for
+            .iter()
+            .cloned()
+            .collect::<Vec<TaskId>>();
+        dependents.iter().try_for_each(|id| self.refresh_blocked(id))
+    }
 
+    /// After a leaf is done: mark done every ancestor whose children all are, and unblock the tasks
+    /// depending on it
+    fn promote_done(&mut self, task_id: &TaskId) -> Result<(), Error> {
         task_id
             .clone()
             .path()
             .rev()
             .try_for_each(|id| {
                 if self.children_are_done(&id) {
-                    self.get_mut(&id)?.status = TaskStatus::Done;
+                    self.set_status(&id, TaskStatus::Done)?;
                 }
                 Ok(())
-            })
+            })?;
+
+        // the tasks depending on this one may not be blocked anymore
+        let dependents = self.get(task_id)?
+            .dependency_for
+            .iter()
+            .cloned()
+            .collect::<Vec<TaskId>>();
+        dependents.iter().try_for_each(|id| self.refresh_blocked(id))
     }
 
-    pub(crate) fn set_planned_value(&mut self, task_id: &TaskId, planned_value: f64) -> Result<(), Error> {
+    /// Returns the change applied to the project's total planned value
+    pub(crate) fn set_planned_value(&mut self, task_id: &TaskId, planned_value: f64) -> Result<f64, Error> {
         let parent_id = task_id.parent()?;
         let mut task = self.get_mut(&task_id)?;
         // can't set actual cost of trunk node
@@ -286,52 +1637,190 @@ impl Tasks {
             return Err(Error::TrunkCannotChangeValue(task_id.clone()));
         }
         let old_planned_value = task.planned_value;
+        if old_planned_value == planned_value {
+            return Ok(0.0);
+        }
         task.planned_value = planned_value;
         let diff = planned_value - old_planned_value;
 
         self.apply_along_path(&parent_id, |mut task| {
             task.planned_value += diff;
-        })
+        })?;
+        Ok(diff)
     }
 
     pub fn to_dot_str(&self) -> String {
+        self.plain_dot_str(false)
+    }
+
+    /// Graph with plain text labels, optionally styling tree edges by the progress of the subtree
+    /// they lead into
+    pub(crate) fn plain_dot_str(&self, progress_edges: bool) -> String {
+        let mut s = format!("digraph G {{\n{}\n", self.dot_graph_attributes());
+        self.subtasks_to_dot_str(&TaskId::get_root_id(), progress_edges, &mut s);
+        s.push('}');
+        s
+    }
+
+    pub(crate) fn dot_graph_attributes(&self) -> String {
+        let metrics = self.metrics();
         let stats = format!(
-            "earned value: {}, spi: {}, sv: {}, cpi: {}, cv: {}",
-            self.earned_value(),
-            self.spi(),
-            self.sv(),
-            self.cpi(),
-            self.cv());
-        format!(
-            "digraph G {{\ngraph [label=\"{}\", rankdir=TB, splines=true, layout=dot, overlap=true, newrank=true];\n{}}}",
-            stats,
-            self.subtasks_to_dot_str(&TaskId::get_root_id()))
-    }
-
-    fn subtasks_to_dot_str(&self, root_id: &TaskId) -> String {
+            "earned value: {}, spi: {}, sv: {}, cpi: {}, cv: {}, eac: {}, vac: {}, tcpi: {}",
+            metrics.earned_value,
+            metrics.spi,
+            metrics.sv,
+            metrics.cpi,
+            metrics.cv,
+            metrics.eac,
+            metrics.vac,
+            metrics.tcpi);
+        format!("graph [label=\"{}\", rankdir=TB, splines=true, layout=dot, overlap=true, newrank=true];", stats)
+    }
+
+    /// Schedule network: only the leaf tasks and their dependency edges, colored by status
+    pub fn to_dependency_dot_str(&self) -> String {
+        let leaves = self.subtree_leaves(&TaskId::get_root_id()).collect::<Vec<&Task>>();
         let mut s = String::new();
+        leaves.iter().for_each(|task| {
+            let label = escape_dot_label(&format!("{} - {}", task.id(), task.name()));
+            s += &format!("\t\"{}\" [label=\"{}\", color={}];\n", task.id(), label, task.status.to_color());
+        });
+        leaves.iter().for_each(|task| {
+            let mut dependencies = task.dependencies
+                .iter()
+                .filter(|id| self.get(id).is_ok())
+                .collect::<Vec<&TaskId>>();
+            dependencies.sort();
+            dependencies.iter().for_each(|dependency_id| {
+                s += &format!("\t\"{}\" -> \"{}\"\n", task.id(), dependency_id);
+            });
+        });
+        format!("digraph G {{\ngraph [rankdir=LR];\n{}}}", s)
+    }
+
+    /// Append the edges of a subtree to `s`, so the whole graph is built in a single buffer
+    fn subtasks_to_dot_str(&self, root_id: &TaskId, progress_edges: bool, s: &mut String) {
         let root = self.get(root_id).unwrap();
-        let root_str = root.to_dot_str();
+        let root_str = escape_dot_label(&root.to_dot_str());
+        if let Some(description) = root.description() {
+            *s += &format!("\t\"{}\" [tooltip=\"{}\"]\n", root_str, escape_dot_label(description));
+        }
 
         root.child_ids().for_each(|child_id| {
             let child = self.get(&child_id).unwrap();
-            s += &format!("\t\"{}\" -> \"{}\" [style=solid, weight=100, constraint=true]\n", root_str, child.to_dot_str());
+            *s += &format!("\t\"{}\" -> \"{}\" [{}]\n", root_str, escape_dot_label(&child.to_dot_str()), self.tree_edge_attributes(&child_id, progress_edges));
         });
         root.child_ids().for_each(|child_id| {
-            s += &self.subtasks_to_dot_str(&child_id);
+            self.subtasks_to_dot_str(&child_id, progress_edges, s);
         });
 
         // dependencies
         root.dependencies.iter()
-            .map(|child_id| self.get(&child_id).unwrap())
+            .map(|child_id| self.get(child_id).unwrap())
             .for_each(|child| {
-                s += &format!("\t\"{}\" -> \"{}\" [style=dashed, weight=0, constraint=false]\n", root_str, child.to_dot_str());
+                *s += &format!("\t\"{}\" -> \"{}\" [style=dashed, weight=0, constraint=false]\n", root_str, escape_dot_label(&child.to_dot_str()));
             });
-        s
     }
 
-    fn subtasks_to_tree_str(&self, root_id: &TaskId, prefix: &str) -> String {
-        let mut s = String::new();
+    /// Ids of every task in pre-order (parents before children, siblings by index), starting
+    /// with the root. Since these are owned, the tasks can be mutated while iterating over them
+    pub fn dfs_ids(&self) -> Vec<TaskId> {
+        let mut ids = Vec::with_capacity(self.len());
+        self.subtree_dfs_ids(&TaskId::get_root_id(), &mut ids);
+        ids
+    }
+
+    /// First leaf task that isn't done, following the reading order of the plan (pre-order)
+    pub fn next_todo(&self) -> Option<&Task> {
+        self.subtree_leaves(&TaskId::get_root_id())
+            .find(|task| task.status != TaskStatus::Done)
+    }
+
+    fn subtree_dfs_ids(&self, root_id: &TaskId, ids: &mut Vec<TaskId>) {
+        if let Ok(root) = self.get(root_id) {
+            ids.push(root_id.clone());
+            root.child_ids().for_each(|child_id| self.subtree_dfs_ids(&child_id, ids));
+        }
+    }
+
+    /// Groups of trunk tasks whose subtrees are structurally identical (same shape and names,
+    /// ignoring ids), so copy-pasted branches can be consolidated. Groups are in pre-order
+    pub fn duplicate_subtrees(&self) -> Vec<Vec<TaskId>> {
+        let mut hashes = HashMap::new();
+        self.subtree_shape_hash(&TaskId::get_root_id(), &mut hashes);
+
+        let mut groups: Vec<Vec<TaskId>> = Vec::new();
+        let mut group_idx: HashMap<u64, usize> = HashMap::new();
+        self.dfs_ids()
+            .into_iter()
+            .filter(|id| self.get(id).unwrap().is_trunk())
+            .for_each(|id| {
+                let idx = *group_idx.entry(hashes[&id]).or_insert_with(|| {
+                    groups.push(Vec::new());
+                    groups.len() - 1
+                });
+                groups[idx].push(id);
+            });
+        groups.retain(|group| group.len() > 1);
+        groups
+    }
+
+    /// Hash over the ids, names, values and statuses of a subtree, stable across runs, so callers
+    /// can skip re-rendering branches that didn't change
+    pub fn subtree_hash(&self, task_id: &TaskId) -> Result<u64, Error> {
+        self.get(task_id)?;
+        Ok(self.subtree_hashes_under(task_id, &mut HashMap::new()))
+    }
+
+    /// `subtree_hash` of every task, to be compared later with `changed_subtrees`
+    pub fn subtree_hashes(&self) -> HashMap<TaskId, u64> {
+        let mut hashes = HashMap::with_capacity(self.len());
+        self.subtree_hashes_under(&TaskId::get_root_id(), &mut hashes);
+        hashes
+    }
+
+    /// Sorted ids of the tasks whose `subtree_hash` isn't the one in `prev_hashes` (or that weren't
+    /// there at all), i.e. the branches to sync again
+    pub fn changed_subtrees(&self, prev_hashes: &HashMap<TaskId, u64>) -> Vec<TaskId> {
+        let mut changed = self.subtree_hashes()
+            .into_iter()
+            .filter(|(id, hash)| prev_hashes.get(id) != Some(hash))
+            .map(|(id, _)| id)
+            .collect::<Vec<TaskId>>();
+        changed.sort();
+        changed
+    }
+
+    fn subtree_hashes_under(&self, root_id: &TaskId, hashes: &mut HashMap<TaskId, u64>) -> u64 {
+        let root = self.get(root_id).unwrap();
+        let mut hasher = StableHasher::default();
+        root.id().hash(&mut hasher);
+        root.name().hash(&mut hasher);
+        root.get_planned_value().to_bits().hash(&mut hasher);
+        root.get_actual_cost().to_bits().hash(&mut hasher);
+        root.status.to_string().hash(&mut hasher);
+        root.child_ids().for_each(|child_id| {
+            self.subtree_hashes_under(&child_id, hashes).hash(&mut hasher);
+        });
+        let hash = hasher.finish();
+        hashes.insert(root_id.clone(), hash);
+        hash
+    }
+
+    fn subtree_shape_hash(&self, root_id: &TaskId, hashes: &mut HashMap<TaskId, u64>) -> u64 {
+        let root = self.get(root_id).unwrap();
+        let mut hasher = DefaultHasher::new();
+        root.name().hash(&mut hasher);
+        root.num_child.hash(&mut hasher);
+        root.child_ids().for_each(|child_id| {
+            self.subtree_shape_hash(&child_id, hashes).hash(&mut hasher);
+        });
+        let hash = hasher.finish();
+        hashes.insert(root_id.clone(), hash);
+        hash
+    }
+
+    fn subtasks_to_tree_lines<'a>(&'a self, root_id: &TaskId, prefix: &str, lines: &mut Vec<(String, &'a Task)>) {
         let root = self.get(root_id).unwrap();
 
         root.child_ids().for_each(|child_id| {
@@ -339,25 +1828,80 @@ impl Tasks {
 
             match self.next_sibling(&child_id) {
                 Ok(_) => {
-                    s += &format!("{}├─ {}\n", prefix, child);
-                    s += &self.subtasks_to_tree_str(&child_id, &format!("{}│  ", prefix));
+                    lines.push((format!("{}├─ ", prefix), child));
+                    self.subtasks_to_tree_lines(&child_id, &format!("{}│  ", prefix), lines);
                 },
                 Err(_) => {
-                    s += &format!("{}└─ {}\n", prefix, child);
-                    s += &self.subtasks_to_tree_str(&child_id, &format!("{}   ", prefix));
+                    lines.push((format!("{}└─ ", prefix), child));
+                    self.subtasks_to_tree_lines(&child_id, &format!("{}   ", prefix), lines);
                 }
             }
         });
-        s
+    }
+
+    /// Tree lines in display order, as (indentation prefix, task) pairs, starting with the root
+    fn tree_lines(&self) -> Vec<(String, &Task)> {
+        let root_id = TaskId::get_root_id();
+        let mut lines = vec![(String::new(), self.get(&root_id).unwrap())];
+        self.subtasks_to_tree_lines(&root_id, "", &mut lines);
+        lines
     }
 
     pub fn to_tree_str(&self) -> String {
-        let root_id = &TaskId::get_root_id();
-        let root = self.get(root_id).unwrap();
-        format!(
-            "{}\n{}",
-            root,
-            self.subtasks_to_tree_str(&TaskId::get_root_id(), ""))
+        self.tree_lines()
+            .iter()
+            .map(|(prefix, task)| format!("{}{}\n", prefix, task))
+            .collect()
+    }
+
+    /// Same as `to_tree_str`, but with each task wrapped in ANSI escapes: green when done, red when
+    /// blocked and dimmed when it's a trunk. Without `color` it's the same as `to_tree_str`, for
+    /// output that isn't going to a terminal
+    pub fn to_tree_str_colored(&self, color: bool) -> String {
+        if !color {
+            return self.to_tree_str();
+        }
+        self.tree_lines()
+            .iter()
+            .map(|(prefix, task)| {
+                let mut codes = Vec::new();
+                if !task.is_leaf() {
+                    codes.push("2");
+                }
+                match task.status {
+                    TaskStatus::Done => codes.push("32"),
+                    TaskStatus::Blocked => codes.push("31"),
+                    TaskStatus::InProgress => {},
+                }
+                if codes.is_empty() {
+                    format!("{}{}\n", prefix, task)
+                } else {
+                    format!("{}\x1b[{}m{}\x1b[0m\n", prefix, codes.join(";"), task)
+                }
+            })
+            .collect()
+    }
+
+    /// Same as `to_tree_str`, but with a right-aligned `planned value/actual cost` column after
+    /// each task, aligned regardless of the task depth
+    pub fn to_tree_str_with_values(&self) -> String {
+        let lines = self.tree_lines()
+            .iter()
+            .map(|(prefix, task)| (
+                format!("{}{}", prefix, task),
+                format!("{}/{}", task.get_planned_value(), task.get_actual_cost())
+            ))
+            .collect::<Vec<(String, String)>>();
+        let tree_width = lines.iter().map(|(tree, _)| tree.chars().count()).max().unwrap_or(0);
+        let values_width = lines.iter().map(|(_, values)| values.len()).max().unwrap_or(0);
+
+        lines
+            .iter()
+            .map(|(tree, values)| {
+                let padding = " ".repeat(tree_width - tree.chars().count());
+                format!("{}{}  {:>width$}\n", tree, padding, values, width = values_width)
+            })
+            .collect()
     }
 
     pub fn get(&self, task_id: &TaskId) -> Result<&Task, Error> {
@@ -371,17 +1915,26 @@ impl Tasks {
     }
 
     pub(crate) fn insert(&mut self, task_id: TaskId, task: Task) {
-        self.store.insert(task_id, task);
+        self.update_counts(&task, true);
+        if let Some(old_task) = self.store.insert(task_id, task) {
+            self.update_counts(&old_task, false);
+        }
     }
 
     pub fn len(&self) -> usize {
         self.store.len()
     }
 
+    /// Whether tasks are kept sorted by id, see `new_ordered`
+    pub fn is_ordered(&self) -> bool {
+        self.store.is_ordered()
+    }
+
+    /// Leaf tasks, in id order. The root isn't one even when the project has no tasks yet
     pub fn get_tasks(&self) -> impl Iterator<Item=&Task> {
         self.store
-            .values()
-            .filter(|task| task.is_leaf())
+            .sorted_values()
+            .filter(|task| Self::is_leaf_task(task))
     }
 
     pub fn get_todo_tasks(&self) -> impl Iterator<Item=&Task> {
@@ -394,54 +1947,57 @@ impl Tasks {
             .filter(|task| task.status == TaskStatus::InProgress)
     }
 
+    pub fn get_blocked_tasks(&self) -> impl Iterator<Item=&Task> {
+        self.get_tasks()
+            .filter(|task| task.status == TaskStatus::Blocked)
+    }
+
     pub fn get_done_tasks(&self) -> impl Iterator<Item=&Task> {
         self.get_tasks()
             .filter(|task| task.status == TaskStatus::Done)
     }
 
-    pub fn get_dependency_roots(&self) -> impl Iterator<Item=&Task> {
-        self.get_tasks()
-            .filter(|task| task.dependency_for.is_empty())
+    /// Leaf tasks not done yet whose due date is before `today`
+    pub fn get_overdue_tasks(&self, today: NaiveDate) -> impl Iterator<Item=&Task> {
+        self.get_todo_tasks()
+            .filter(move |task| task.due_date.is_some_and(|due_date| due_date < today))
     }
 
-    fn dfs_has_cycle<'a>(&'a self, stack: &mut Vec<&'a TaskId>, visited: &mut HashMap<&'a TaskId, DFSVertexStatus> ) -> Result<bool, Error> {
+    /// Sorted ids of every task (trunks included) matching `predicate`. Being owned, they can be
+    /// used to update the tasks afterwards
+    pub fn find_ids<P: Fn(&Task) -> bool>(&self, predicate: P) -> Vec<TaskId> {
+        let mut ids = self.store
+            .values()
+            .filter(|task| predicate(task))
+            .map(|task| task.id().clone())
+            .collect::<Vec<TaskId>>();
+        ids.sort();
+        ids
+    }
 
-        let top = stack.last().cloned().unwrap();
-        for neighbour in self.get(top)?.dependencies.iter() {
-            match visited.get(neighbour) {
-                Some(DFSVertexStatus::InStack) => {
-                    return Ok(true);
-                },
-                Some(DFSVertexStatus::Done) => {},
-                Some(DFSVertexStatus::NotVisited) | None => {
-                    stack.push(neighbour);
-                    visited.insert(neighbour, DFSVertexStatus::InStack);
-                    if self.dfs_has_cycle(stack, visited)? {
-                        return Ok(true);
-                    }
-                }
-            }
-        }
-        visited.insert(top, DFSVertexStatus::Done);
-        stack.pop();
-        Ok(false)
+    /// Sorted ids of the trunks with no planned value anywhere under them, i.e. whole branches
+    /// nobody estimated yet
+    pub fn unestimated_branches(&self) -> Vec<TaskId> {
+        // the leaves rather than the rolled-up value, which may be left a rounding error away from
+        // zero, or add up to zero from nonzero estimates
+        self.find_ids(|task| task.is_trunk() && self.subtree_leaves(task.id()).all(|leaf| leaf.get_planned_value() == 0.0))
     }
 
-    fn dependency_cycle_exists(&self) -> Result<bool, Error> {
-        let mut visited : HashMap<&TaskId, DFSVertexStatus> = self.get_tasks()
-            .map(|t| (t.id(), DFSVertexStatus::NotVisited))
-            .collect::<HashMap<&TaskId, DFSVertexStatus>>();
+    /// The `n` open leaf tasks with the highest planned value, highest first
+    pub fn top_remaining(&self, n: usize) -> Vec<&Task> {
+        let mut tasks = self.get_todo_tasks().collect::<Vec<&Task>>();
+        tasks.sort_by(|a, b| {
+            b.get_planned_value()
+                .total_cmp(&a.get_planned_value())
+                .then_with(|| a.id().cmp(b.id()))
+        });
+        tasks.truncate(n);
+        tasks
+    }
 
-        for task_id in self.get_tasks().map(|t| t.id()) {
-            if let Some(DFSVertexStatus::NotVisited) = visited.get(task_id) {
-                let mut stack : Vec<&TaskId> = vec![task_id];
-                visited.insert(task_id, DFSVertexStatus::InStack);
-                if self.dfs_has_cycle(&mut stack, &mut visited)? {
-                    return Ok(true);
-                }
-            }
-        }
-        Ok(false)
+    pub fn get_dependency_roots(&self) -> impl Iterator<Item=&Task> {
+        self.get_tasks()
+            .filter(|task| task.dependency_for.is_empty())
     }
 }
 
@@ -449,11 +2005,12 @@ impl Tasks {
 mod tests {
 
     use super::*;
+    use crate::task::metrics::EvmMetrics;
 
     #[test]
     fn tasks() {
         let mut tasks = Tasks::new("Project");
-        let members = Members::new();
+        let mut members = Members::new();
 
         let root = TaskId::get_root_id();
         let task_id_1 = TaskId::new(vec![1]);
@@ -479,7 +2036,7 @@ mod tests {
 
         assert_eq!(tasks.get(&task_id_1_1), Ok(&Task::new(TaskId::new(vec![1,1]), "Create Task struct")));
         assert_eq!(tasks.get_mut(&task_id_1_1), Ok(&mut Task::new(TaskId::new(vec![1,1]), "Create Task struct")));
-        assert_eq!(tasks.set_planned_value(&task_id_1_1, 2.0), Ok(()));
+        assert_eq!(tasks.set_planned_value(&task_id_1_1, 2.0), Ok(2.0));
         assert_eq!(tasks.planned_value(), 2.0);
         assert_eq!(tasks.get(&task_id_1_1).unwrap().get_planned_value(), 2.0);
         assert_eq!(tasks.get(&task_id_1).unwrap().get_planned_value(), 2.0);
@@ -489,7 +2046,7 @@ mod tests {
 
         assert_eq!(tasks.get(&task_id_2_1), Ok(&Task::new(TaskId::new(vec![2,1]), "Create argument parser")));
         assert_eq!(tasks.get_mut(&task_id_2_1), Ok(&mut Task::new(TaskId::new(vec![2,1]), "Create argument parser")));
-        assert_eq!(tasks.set_planned_value(&task_id_2_1, 7.0), Ok(()));
+        assert_eq!(tasks.set_planned_value(&task_id_2_1, 7.0), Ok(7.0));
         assert_eq!(tasks.planned_value(), 9.0);
         assert_eq!(tasks.get(&task_id_2_1).unwrap().get_planned_value(), 7.0);
         assert_eq!(tasks.get(&task_id_2_2).unwrap().get_planned_value(), 0.0);
@@ -497,7 +2054,7 @@ mod tests {
 
         assert_eq!(tasks.get(&task_id_2_2), Ok(&Task::new(TaskId::new(vec![2,2]), "Create help menu")));
         assert_eq!(tasks.get_mut(&task_id_2_2), Ok(&mut Task::new(TaskId::new(vec![2,2]), "Create help menu")));
-        assert_eq!(tasks.set_planned_value(&task_id_2_2, 33.0), Ok(()));
+        assert_eq!(tasks.set_planned_value(&task_id_2_2, 33.0), Ok(33.0));
         assert_eq!(tasks.planned_value(), 42.0);
         assert_eq!(tasks.get(&task_id_2_1).unwrap().get_planned_value(), 7.0);
         assert_eq!(tasks.get(&task_id_2_2).unwrap().get_planned_value(), 33.0);
@@ -508,23 +2065,23 @@ mod tests {
 
         assert_eq!(tasks.get(&task_id_3_1), Ok(&Task::new(TaskId::new(vec![3,1]), "Create plot visualizer")));
         assert_eq!(tasks.get_mut(&task_id_3_1), Ok(&mut Task::new(TaskId::new(vec![3,1]), "Create plot visualizer")));
-        assert_eq!(tasks.set_planned_value(&task_id_3_1, 20.0), Ok(()));
+        assert_eq!(tasks.set_planned_value(&task_id_3_1, 20.0), Ok(20.0));
         assert_eq!(tasks.planned_value(), 62.0);
         assert_eq!(tasks.get(&task_id_3_1).unwrap().get_planned_value(), 20.0);
         assert_eq!(tasks.get(&task_id_3).unwrap().get_planned_value(), 20.0);
-        assert_eq!(tasks.remove(&task_id_2_1, &members), Ok(Task::new(TaskId::new(vec![2,1]), "Create argument parser")));
+        assert_eq!(tasks.remove(&task_id_2_1, &mut members), Ok(Task::new(TaskId::new(vec![2,1]), "Create argument parser")));
 
         assert_eq!(tasks.planned_value(), 55.0);
         assert_eq!(tasks.get(&task_id_2_1), Ok(&Task::new(TaskId::new(vec![2, 1]), "Create help menu")));
         assert_eq!(tasks.get(&task_id_2), Ok(&Task::new(TaskId::new(vec![2]), "Create CLI tool")));
         assert_eq!(tasks.get(&task_id_2).unwrap().get_planned_value(), 33.0);
 
-        assert_eq!(tasks.remove(&task_id_2, &members), Err(Error::TrunkCannotBeRemoved(task_id_2.clone())));
+        assert_eq!(tasks.remove(&task_id_2, &mut members), Err(Error::TrunkCannotBeRemoved(task_id_2.clone())));
         assert_eq!(tasks.planned_value(), 55.0);
-        assert_eq!(tasks.remove(&task_id_2_1, &members), Ok(Task::new(TaskId::new(vec![2,1]), "Create help menu")));
+        assert_eq!(tasks.remove(&task_id_2_1, &mut members), Ok(Task::new(TaskId::new(vec![2,1]), "Create help menu")));
         assert_eq!(tasks.planned_value(), 22.0);
         assert_eq!(tasks.get(&task_id_2).unwrap().get_planned_value(), 0.0);
-        assert_eq!(tasks.remove(&task_id_2, &members), Ok(Task::new(TaskId::new(vec![2]), "Create CLI tool")));
+        assert_eq!(tasks.remove(&task_id_2, &mut members), Ok(Task::new(TaskId::new(vec![2]), "Create CLI tool")));
         assert_eq!(tasks.planned_value(), 22.0);
 
         assert_eq!(tasks.get(&task_id_1), Ok(&Task::new(TaskId::new(vec![1]), "Create WSB")));
@@ -540,6 +2097,2010 @@ mod tests {
         assert_eq!(tasks.get_mut(&task_id_2_1), Ok(&mut Task::new(TaskId::new(vec![2,1]), "Create plot visualizer")));
 
         assert_eq!(tasks.add_dependency(&task_id_1_1, &task_id_2_1), Ok(()));
-        assert_eq!(tasks.add_dependency(&task_id_2_1, &task_id_1_1), Err(Error::EdgeCreationLeadsToCycle(task_id_2_1.clone(), task_id_1_1.clone())));
+        assert_eq!(tasks.add_dependency(&task_id_2_1, &task_id_1_1), Err(Error::DependencyCycle(task_id_2_1.clone(), task_id_1_1.clone())));
+    }
+
+    #[test]
+    fn tree_str_with_values() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                    ("1.1", "Tables"),
+            ("", "Build"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 1, 1]), 2.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2]), 10.5).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![2]), 12.0).unwrap();
+
+        assert_eq!(tasks.to_tree_str_with_values(), concat!(
+            "Project ✗                        12.5/12\n",
+            "├─ 1 - Design ✗ -> []                2/0\n",
+            "│  └─ 1.1 - Schema ✗ -> []           2/0\n",
+            "│     └─ 1.1.1 - Tables ✗ -> []      2/0\n",
+            "└─ 2 - Build ✔ -> []             10.5/12\n",
+        ));
+    }
+
+    #[test]
+    fn consistency() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+            ("", "Build"),
+        ]).unwrap();
+        assert!(tasks.is_consistent());
+        assert!(tasks.validate().is_empty());
+
+        tasks.get_mut(&TaskId::new(vec![1])).unwrap().num_child = 2;
+        assert!(!tasks.is_consistent());
+        assert_eq!(tasks.validate(), vec![Error::MissingChild(TaskId::new(vec![1]), TaskId::new(vec![1, 2]))]);
+
+        tasks.get_mut(&TaskId::get_root_id()).unwrap().num_child = 1;
+        tasks.get_mut(&TaskId::new(vec![1])).unwrap().num_child = 1;
+        assert!(!tasks.is_consistent());
+        assert_eq!(tasks.validate(), vec![Error::OrphanTask(TaskId::new(vec![2]))]);
+    }
+
+    #[test]
+    fn dfs_ids() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Create WSB"),
+                ("1", "Create Task struct"),
+            ("", "Create CLI tool"),
+                ("2", "Create argument parser"),
+                ("2", "Create help menu"),
+            ("", "Create GUI tool"),
+        ]).unwrap();
+
+        assert_eq!(tasks.dfs_ids(), vec![
+            TaskId::get_root_id(),
+            TaskId::new(vec![1]),
+            TaskId::new(vec![1, 1]),
+            TaskId::new(vec![2]),
+            TaskId::new(vec![2, 1]),
+            TaskId::new(vec![2, 2]),
+            TaskId::new(vec![3]),
+        ]);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn spi_weighted() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Write docs"),
+            ("", "Fix typos"),
+            ("", "Update README"),
+            ("", "Bump version"),
+            ("", "Rewrite engine"),
+        ]).unwrap();
+        (1..=4).for_each(|i| {
+            tasks.set_planned_value(&TaskId::new(vec![i]), 1.0).unwrap();
+        });
+        tasks.set_planned_value(&TaskId::new(vec![5]), 96.0).unwrap();
+        (1..=4).for_each(|i| tasks.set_actual_cost(&TaskId::new(vec![i]), 1.0).unwrap());
+
+        assert_eq!(tasks.completion_percentage(), 0.8);
+        assert_eq!(tasks.spi_weighted(), 0.04);
+        assert_eq!(tasks.spi(), tasks.spi_weighted());
+    }
+
+    #[test]
+    fn with_capacity() {
+        let tasks = Tasks::with_capacity("Project", 1000);
+        assert!(tasks.store.capacity() >= 1000);
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks.name(), "Project");
+    }
+
+    #[test]
+    fn duplicate_subtrees() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Release"),
+                ("1", "Build"),
+                ("1", "Publish"),
+            ("", "Release"),
+                ("2", "Build"),
+                ("2", "Publish"),
+            ("", "Release"),
+                ("3", "Build"),
+        ]).unwrap();
+
+        assert_eq!(tasks.duplicate_subtrees(), vec![vec![TaskId::new(vec![1]), TaskId::new(vec![2])]]);
+    }
+
+    #[test]
+    fn from_template() {
+        let mut template = Template::new("Website");
+        template
+            .add_entry("", "Design", 0.0)
+                .add_entry("1", "Wireframes", 3.0)
+                .add_entry("1", "Mockups", 5.0)
+            .add_entry("", "Implementation", 0.0)
+                .add_entry("2", "Frontend", 8.0);
+
+        let tasks = Tasks::from_template(&template).unwrap();
+        assert_eq!(tasks.name(), "Website");
+        assert_eq!(tasks.len(), 6);
+        assert_eq!(tasks.get(&TaskId::new(vec![1, 2])), Ok(&Task::new(TaskId::new(vec![1, 2]), "Mockups")));
+        assert_eq!(tasks.get(&TaskId::new(vec![2, 1])), Ok(&Task::new(TaskId::new(vec![2, 1]), "Frontend")));
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().get_planned_value(), 8.0);
+        assert_eq!(tasks.get(&TaskId::new(vec![2])).unwrap().get_planned_value(), 8.0);
+        assert_eq!(tasks.planned_value(), 16.0);
+
+        template.add_entry("2.1", "Components", 1.0);
+        assert_eq!(Tasks::from_template(&template).err(), Some(Error::TrunkCannotChangeValue(TaskId::new(vec![2, 1]))));
+    }
+
+    #[test]
+    fn apply_status_updates() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+        ]).unwrap();
+
+        let updates = HashMap::from([
+            (TaskId::new(vec![1, 1]), TaskStatus::Done),
+            (TaskId::new(vec![1, 2]), TaskStatus::Done),
+            (TaskId::new(vec![1]), TaskStatus::Done),
+            (TaskId::new(vec![3]), TaskStatus::Done),
+        ]);
+        assert_eq!(tasks.apply_status_updates(&updates), Ok(vec![TaskId::new(vec![1]), TaskId::new(vec![3])]));
+        assert_eq!(tasks.get(&TaskId::new(vec![1, 1])).unwrap().status, TaskStatus::Done);
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().status, TaskStatus::Done);
+        assert_eq!(tasks.get(&TaskId::get_root_id()).unwrap().status, TaskStatus::InProgress);
+
+        let updates = HashMap::from([(TaskId::new(vec![2]), TaskStatus::Done)]);
+        assert_eq!(tasks.apply_status_updates(&updates), Ok(vec![]));
+        assert_eq!(tasks.get(&TaskId::get_root_id()).unwrap().status, TaskStatus::Done);
+
+        // blocked follows the dependencies, whatever the update says
+        tasks.add_task(TaskId::get_root_id(), "Release").unwrap();
+        tasks.add_dependency(&TaskId::new(vec![3]), &TaskId::new(vec![2])).unwrap();
+        let updates = HashMap::from([
+            (TaskId::new(vec![2]), TaskStatus::Blocked),
+            (TaskId::new(vec![1, 1]), TaskStatus::Blocked),
+        ]);
+        assert_eq!(tasks.apply_status_updates(&updates), Ok(vec![]));
+        assert_eq!(tasks.get(&TaskId::new(vec![2])).unwrap().status, TaskStatus::InProgress);
+        assert_eq!(tasks.get(&TaskId::new(vec![1, 1])).unwrap().status, TaskStatus::InProgress);
+        assert_eq!(tasks.get(&TaskId::new(vec![3])).unwrap().status, TaskStatus::Blocked);
+        let updates = HashMap::from([(TaskId::new(vec![3]), TaskStatus::InProgress)]);
+        assert_eq!(tasks.apply_status_updates(&updates), Ok(vec![]));
+        assert_eq!(tasks.get(&TaskId::new(vec![3])).unwrap().status, TaskStatus::Blocked);
+    }
+
+    #[test]
+    fn critical_ratio() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Build"),
+                ("1", "Backend"),
+                ("1", "Frontend"),
+        ]).unwrap();
+        let backend = TaskId::new(vec![1, 1]);
+        let frontend = TaskId::new(vec![1, 2]);
+        tasks.set_planned_value(&backend, 10.0).unwrap();
+        tasks.set_planned_value(&frontend, 10.0).unwrap();
+        tasks.set_actual_cost(&backend, 5.0).unwrap();
+
+        // spi = 1.0, cpi = 2.0
+        assert_eq!(tasks.critical_ratio(&backend), Ok(2.0));
+        // nothing earned yet
+        assert_eq!(tasks.critical_ratio(&frontend), Ok(0.0));
+        // spi = 10 / 20, cpi = 10 / 5
+        assert_eq!(tasks.critical_ratio(&TaskId::new(vec![1])), Ok(1.0));
+        assert_eq!(tasks.critical_ratio(&TaskId::new(vec![2])), Err(Error::TaskNotFound(TaskId::new(vec![2]))));
+    }
+
+    #[test]
+    fn subtree_hash() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+            ("", "Build"),
+                ("2", "Backend"),
+        ]).unwrap();
+        let design = TaskId::new(vec![1]);
+        let build = TaskId::new(vec![2]);
+        let root_hash = tasks.subtree_hash(&TaskId::get_root_id()).unwrap();
+        let design_hash = tasks.subtree_hash(&design).unwrap();
+        let build_hash = tasks.subtree_hash(&build).unwrap();
+        assert_eq!(tasks.clone().subtree_hash(&design), Ok(design_hash));
+
+        tasks.set_planned_value(&TaskId::new(vec![2, 1]), 4.0).unwrap();
+        assert_ne!(tasks.subtree_hash(&TaskId::get_root_id()).unwrap(), root_hash);
+        assert_ne!(tasks.subtree_hash(&build).unwrap(), build_hash);
+        assert_eq!(tasks.subtree_hash(&design), Ok(design_hash));
+        assert_eq!(tasks.subtree_hash(&TaskId::new(vec![3])), Err(Error::TaskNotFound(TaskId::new(vec![3]))));
+    }
+
+    #[test]
+    fn leaf_depth_stats() {
+        let mut tasks = Tasks::new("Project");
+        assert_eq!(tasks.leaf_depth_stats(), (0, 0, 0.0));
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+        ]).unwrap();
+        assert_eq!(tasks.leaf_depth_stats(), (1, 2, 5.0 / 3.0));
+    }
+
+    #[test]
+    fn reparent() {
+        let mut tasks = Tasks::new("Project");
+        let mut members = Members::new();
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+                ("2", "Backend"),
+                ("2", "Frontend"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 1]), 2.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 2]), 3.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2, 1]), 5.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2, 2]), 7.0).unwrap();
+        members.insert("alice".to_string()).unwrap();
+        members.assign_task_to_member(TaskId::new(vec![2, 2]), "alice", &mut tasks).unwrap();
+
+        // "API" keeps its index, "Frontend" makes room for it
+        assert_eq!(tasks.reparent(&TaskId::new(vec![1, 2]), &TaskId::new(vec![2]), true, &mut members), Ok(TaskId::new(vec![2, 2])));
+        assert!(tasks.is_consistent());
+        assert_eq!(tasks.get(&TaskId::new(vec![2, 2])).unwrap().name(), "API");
+        assert_eq!(tasks.get(&TaskId::new(vec![2, 3])).unwrap().name(), "Frontend");
+        assert!(members.get("alice").unwrap().is_assigned_to(&TaskId::new(vec![2, 3])));
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().get_planned_value(), 2.0);
+        assert_eq!(tasks.get(&TaskId::new(vec![2])).unwrap().get_planned_value(), 15.0);
+        assert_eq!(tasks.planned_value(), 17.0);
+
+        // without keeping the index, it's appended
+        assert_eq!(tasks.reparent(&TaskId::new(vec![2, 1]), &TaskId::new(vec![1]), false, &mut members), Ok(TaskId::new(vec![1, 2])));
+        assert!(tasks.is_consistent());
+        assert_eq!(tasks.get(&TaskId::new(vec![1, 2])).unwrap().name(), "Backend");
+        assert_eq!(tasks.get(&TaskId::new(vec![2, 1])).unwrap().name(), "API");
+        assert!(members.get("alice").unwrap().is_assigned_to(&TaskId::new(vec![2, 2])));
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().get_planned_value(), 7.0);
+        assert_eq!(tasks.get(&TaskId::new(vec![2])).unwrap().get_planned_value(), 10.0);
+        tasks.debug_assert_rollups();
+
+        assert_eq!(
+            tasks.reparent(&TaskId::new(vec![2]), &TaskId::new(vec![2, 1]), false, &mut members),
+            Err(Error::CannotMoveIntoDescendant(TaskId::new(vec![2]), TaskId::new(vec![2, 1]))));
+    }
+
+    #[test]
+    fn reparent_subtree() {
+        let mut tasks = Tasks::new("Project");
+        let mut members = Members::new();
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+            ("", "Build"),
+                ("2", "Backend"),
+                    ("2.1", "Routes"),
+                    ("2.1", "Storage"),
+                ("2", "Frontend"),
+            ("", "Release"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2, 1, 1]), 2.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2, 1, 2]), 3.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2, 2]), 4.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 1.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![2, 1, 1]), 1.5).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![2, 1, 2]), 2.5).unwrap();
+
+        // "Backend" and everything under it goes under "Design", after "Schema"
+        assert_eq!(tasks.reparent(&TaskId::new(vec![2, 1]), &TaskId::new(vec![1]), false, &mut members), Ok(TaskId::new(vec![1, 2])));
+        assert!(tasks.is_consistent());
+        tasks.debug_assert_rollups();
+        assert_eq!(tasks.get(&TaskId::new(vec![1, 2, 2])).unwrap().name(), "Storage");
+        assert_eq!(tasks.get(&TaskId::new(vec![2, 1])).unwrap().name(), "Frontend");
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().get_planned_value(), 5.0);
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().get_actual_cost(), 5.0);
+        assert_eq!(tasks.get(&TaskId::new(vec![2])).unwrap().get_planned_value(), 4.0);
+        assert_eq!(tasks.get(&TaskId::new(vec![2])).unwrap().get_actual_cost(), 0.0);
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().status, TaskStatus::Done);
+        assert_eq!(tasks.get(&TaskId::new(vec![2])).unwrap().status, TaskStatus::InProgress);
+        assert_eq!(tasks.planned_value(), 9.0);
+    }
+
+    #[test]
+    fn next_todo() {
+        let mut tasks = Tasks::new("Project");
+        assert_eq!(tasks.next_todo(), None);
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+        ]).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 1.0).unwrap();
+        assert_eq!(tasks.next_todo().map(|task| task.id()), Some(&TaskId::new(vec![1, 2])));
+
+        tasks.set_actual_cost(&TaskId::new(vec![1, 2]), 1.0).unwrap();
+        assert_eq!(tasks.next_todo().map(|task| task.id()), Some(&TaskId::new(vec![2])));
+
+        tasks.set_actual_cost(&TaskId::new(vec![2]), 1.0).unwrap();
+        assert_eq!(tasks.next_todo(), None);
+    }
+
+    #[test]
+    fn scale_values() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 1]), 2.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 2]), 3.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2]), 10.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 4.0).unwrap();
+        let before = tasks.clone();
+
+        assert_eq!(tasks.scale_values(-1.0), Err(Error::InvalidScaleFactor));
+        assert_eq!(tasks.scale_values(f64::INFINITY), Err(Error::InvalidScaleFactor));
+        assert_eq!(tasks.scale_values(f64::NAN), Err(Error::InvalidScaleFactor));
+        assert_eq!(tasks.scale_values(1.1), Ok(()));
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+        before.dfs_ids().iter().for_each(|id| {
+            let (old, new) = (before.get(id).unwrap(), tasks.get(id).unwrap());
+            assert!(close(new.get_planned_value(), old.get_planned_value() * 1.1));
+            assert!(close(new.get_actual_cost(), old.get_actual_cost() * 1.1));
+        });
+        assert!(close(tasks.planned_value(), 16.5));
+        assert!(close(tasks.get(&TaskId::new(vec![1])).unwrap().get_planned_value(), 5.5));
+        assert!(close(tasks.actual_cost(), 4.4));
+    }
+
+    #[test]
+    fn dependency_dot_str() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+            ("", "Build"),
+            ("", "Release"),
+        ]).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![2]), &TaskId::new(vec![1, 1])).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![3]), &TaskId::new(vec![2])).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 1.0).unwrap();
+
+        assert_eq!(tasks.to_dependency_dot_str(), concat!(
+            "digraph G {\n",
+            "graph [rankdir=LR];\n",
+            "\t\"1.1\" [label=\"1.1 - Schema\", color=darkgreen];\n",
+            "\t\"2\" [label=\"2 - Build\", color=black];\n",
+            "\t\"3\" [label=\"3 - Release\", color=darkorange];\n",
+            "\t\"2\" -> \"1.1\"\n",
+            "\t\"3\" -> \"2\"\n",
+            "}",
+        ));
+    }
+
+    #[test]
+    fn self_dependency() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+        ]).unwrap();
+        let task_id = TaskId::new(vec![1]);
+        assert_eq!(tasks.add_dependency(&task_id, &task_id), Err(Error::SelfDependency(task_id.clone())));
+        assert!(tasks.get(&task_id).unwrap().dependencies.is_empty());
+        assert!(tasks.get(&task_id).unwrap().dependency_for.is_empty());
+
+        let task = tasks.get_mut(&task_id).unwrap();
+        task.dependencies.insert(task_id.clone());
+        task.dependency_for.insert(task_id.clone());
+        assert_eq!(tasks.validate(), vec![Error::SelfDependency(task_id)]);
+    }
+
+    #[test]
+    fn top_remaining() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+            ("", "Release"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 1]), 20.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 2]), 3.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2]), 8.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![3]), 1.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 20.0).unwrap();
+
+        let ids = |tasks: Vec<&Task>| tasks.into_iter().map(|task| task.id().clone()).collect::<Vec<TaskId>>();
+        assert_eq!(ids(tasks.top_remaining(2)), vec![TaskId::new(vec![2]), TaskId::new(vec![1, 2])]);
+        assert_eq!(tasks.top_remaining(10).len(), 3);
+    }
+
+    #[test]
+    fn is_under() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+                ("2", "Backend"),
+            ("", "Release"),
+        ]).unwrap();
+        let task_id = TaskId::new(vec![2, 1]);
+        assert_eq!(tasks.is_under(&task_id, &TaskId::new(vec![2])), Ok(true));
+        assert_eq!(tasks.is_under(&task_id, &TaskId::get_root_id()), Ok(true));
+        assert_eq!(tasks.is_under(&task_id, &TaskId::new(vec![3])), Ok(false));
+        assert_eq!(tasks.is_under(&task_id, &task_id), Ok(false));
+        assert_eq!(tasks.is_under(&task_id, &TaskId::new(vec![4])), Err(Error::TaskNotFound(TaskId::new(vec![4]))));
+        assert_eq!(tasks.is_under(&TaskId::new(vec![2, 2]), &TaskId::new(vec![2])), Err(Error::TaskNotFound(TaskId::new(vec![2, 2]))));
+    }
+
+    #[test]
+    fn set_planned_value_delta() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+            ("", "Build"),
+        ]).unwrap();
+        let schema = TaskId::new(vec![1, 1]);
+        assert_eq!(tasks.set_planned_value(&schema, 5.0), Ok(5.0));
+        assert_eq!(tasks.set_planned_value(&TaskId::new(vec![2]), 3.0), Ok(3.0));
+
+        let total = tasks.planned_value();
+        let delta = tasks.set_planned_value(&schema, 1.5).unwrap();
+        assert_eq!(delta, -3.5);
+        assert_eq!(tasks.planned_value(), total + delta);
+    }
+
+    #[test]
+    fn find_ids() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+        ]).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 2]), 1.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 1.0).unwrap();
+
+        assert_eq!(
+            tasks.find_ids(|task| task.status == TaskStatus::Done),
+            vec![TaskId::new(vec![1]), TaskId::new(vec![1, 1]), TaskId::new(vec![1, 2])]);
+        assert!(tasks.find_ids(|task| task.name() == "Release").is_empty());
+    }
+
+    #[test]
+    fn member_completion() {
+        let mut tasks = Tasks::new("Project");
+        let mut members = Members::new();
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+            ("", "Release"),
+        ]).unwrap();
+        members.insert("alice".to_string()).unwrap();
+        members.insert("bob".to_string()).unwrap();
+        members.insert("carol".to_string()).unwrap();
+        members.assign_task_to_member(TaskId::new(vec![1]), "alice", &mut tasks).unwrap();
+        members.assign_task_to_member(TaskId::new(vec![2]), "alice", &mut tasks).unwrap();
+        members.assign_task_to_member(TaskId::new(vec![3]), "bob", &mut tasks).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1]), 1.0).unwrap();
+
+        assert_eq!(tasks.member_completion(&members), HashMap::from([
+            ("alice".to_string(), 0.5),
+            ("bob".to_string(), 0.0),
+        ]));
+    }
+
+    #[test]
+    fn align_ids_to() {
+        let mut tasks = Tasks::new("Project");
+        let mut members = Members::new();
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+                ("2", "Backend"),
+            ("", "Release"),
+                ("3", "Changelog"),
+                ("3", "Announce"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 2]), 3.0).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![2, 1]), &TaskId::new(vec![1, 2])).unwrap();
+        members.insert("alice".to_string()).unwrap();
+        members.assign_task_to_member(TaskId::new(vec![1, 2]), "alice", &mut tasks).unwrap();
+
+        let mut other = Tasks::new("Plan");
+        other.expand(&[
+            ("", "Build"),
+                ("1", "Backend"),
+            ("", "Design"),
+                ("2", "API"),
+                ("2", "Schema"),
+            ("", "Release"),
+                ("3", "Announce"),
+                ("3", "Docs"),
+        ]).unwrap();
+
+        let mapping = tasks.align_ids_to(&other, &mut members).unwrap();
+        assert_eq!(mapping, HashMap::from([
+            (TaskId::new(vec![1]), TaskId::new(vec![2])),
+            (TaskId::new(vec![1, 1]), TaskId::new(vec![2, 2])),
+            (TaskId::new(vec![1, 2]), TaskId::new(vec![2, 1])),
+            (TaskId::new(vec![2]), TaskId::new(vec![1])),
+            (TaskId::new(vec![2, 1]), TaskId::new(vec![1, 1])),
+        ]));
+        assert!(tasks.is_consistent());
+        assert_eq!(tasks.get(&TaskId::new(vec![2, 1])).unwrap().name(), "API");
+        assert_eq!(tasks.get(&TaskId::new(vec![2])).unwrap().get_planned_value(), 3.0);
+        assert!(tasks.get(&TaskId::new(vec![1, 1])).unwrap().dependencies.contains(&TaskId::new(vec![2, 1])));
+        assert!(members.get("alice").unwrap().is_assigned_to(&TaskId::new(vec![2, 1])));
+        // "Release" has different children, so they stay where they were
+        assert_eq!(tasks.get(&TaskId::new(vec![3, 1])).unwrap().name(), "Changelog");
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn metrics_without_values() {
+        let mut tasks = Tasks::new("Project");
+        let metrics = [
+            Tasks::completion_percentage, Tasks::earned_value, Tasks::spi, Tasks::spi_weighted,
+            Tasks::sv, Tasks::cpi, Tasks::cv, Tasks::eac, Tasks::vac, Tasks::tcpi,
+        ];
+        for metric in metrics {
+            assert_eq!(metric(&tasks), 0.0);
+        }
+        assert_eq!(tasks.critical_ratio(&TaskId::get_root_id()), Ok(0.0));
+        assert_eq!(tasks.snapshot_as_of(NaiveDate::from_ymd(2022, 10, 3)), EvmMetrics::default());
+
+        // value earned at no cost
+        tasks.expand(&[("", "Design")]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1]), 2.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1]), 0.0).unwrap();
+        assert_eq!(tasks.cpi(), 0.0);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "actual cost of 1 is 4, but its children add up to 3")]
+    fn debug_assert_rollups() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+        ]).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 1.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 2]), 2.0).unwrap();
+        tasks.debug_assert_rollups();
+
+        tasks.get_mut(&TaskId::new(vec![1])).unwrap().actual_cost = 4.0;
+        tasks.debug_assert_rollups();
+    }
+
+    #[test]
+    fn children_by_completion() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+                ("2", "Backend"),
+            ("", "Release"),
+        ]).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 1.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![2, 1]), 1.0).unwrap();
+
+        let children = tasks.children_by_completion(&TaskId::get_root_id())
+            .unwrap()
+            .into_iter()
+            .map(|(task, completion)| (task.name(), completion))
+            .collect::<Vec<(&str, f64)>>();
+        assert_eq!(children, vec![("Build", 1.0), ("Design", 0.5), ("Release", 0.0)]);
+        assert_eq!(tasks.children_by_completion(&TaskId::new(vec![4])), Err(Error::TaskNotFound(TaskId::new(vec![4]))));
+    }
+
+    #[test]
+    fn apply_analogous_estimate() {
+        let mut reference = Tasks::new("Last year");
+        reference.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Release"),
+        ]).unwrap();
+        reference.set_planned_value(&TaskId::new(vec![1, 1]), 2.0).unwrap();
+        reference.set_planned_value(&TaskId::new(vec![1, 2]), 4.0).unwrap();
+        reference.set_planned_value(&TaskId::new(vec![2]), 1.0).unwrap();
+
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "API"),
+            ("", "Build"),
+                ("2", "Schema"),
+                ("2", "Frontend"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2, 2]), 5.0).unwrap();
+
+        tasks.apply_analogous_estimate(&reference, 1.5).unwrap();
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().get_planned_value(), 6.0);
+        assert_eq!(tasks.get(&TaskId::new(vec![2, 1])).unwrap().get_planned_value(), 3.0);
+        assert_eq!(tasks.get(&TaskId::new(vec![2, 2])).unwrap().get_planned_value(), 5.0);
+        assert_eq!(tasks.planned_value(), 14.0);
+        tasks.debug_assert_rollups();
+
+        assert_eq!(tasks.apply_analogous_estimate(&reference, -1.0), Err(Error::InvalidScaleFactor));
+        assert_eq!(tasks.apply_analogous_estimate(&reference, f64::INFINITY), Err(Error::InvalidScaleFactor));
+    }
+
+    #[test]
+    fn maintained_counts() {
+        let mut tasks = Tasks::new("Project");
+        let mut members = Members::new();
+        let assert_counts = |tasks: &Tasks| {
+            let mut recounted = tasks.clone();
+            recounted.recount();
+            assert_eq!(tasks.leaf_count(), recounted.leaf_count());
+            assert_eq!(tasks.done_count(), recounted.done_count());
+        };
+        assert_eq!((tasks.leaf_count(), tasks.done_count()), (0, 0));
+
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+                ("2", "Backend"),
+            ("", "Release"),
+        ]).unwrap();
+        assert_counts(&tasks);
+        assert_eq!(tasks.leaf_count(), 4);
+
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 1.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 2]), 1.0).unwrap();
+        assert_counts(&tasks);
+        assert_eq!(tasks.done_count(), 2);
+
+        tasks.apply_status_updates(&HashMap::from([(TaskId::new(vec![1, 2]), TaskStatus::InProgress)])).unwrap();
+        assert_counts(&tasks);
+        tasks.reparent(&TaskId::new(vec![2, 1]), &TaskId::new(vec![3]), false, &mut members).unwrap();
+        assert_counts(&tasks);
+        tasks.remove(&TaskId::new(vec![1, 1]), &mut members).unwrap();
+        assert_counts(&tasks);
+        tasks.add_task(TaskId::new(vec![2]), "Frontend").unwrap();
+        assert_counts(&tasks);
+        assert_eq!((tasks.leaf_count(), tasks.done_count()), (3, 0));
+    }
+
+    #[test]
+    fn blocked_status() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+        ]).unwrap();
+        let api = TaskId::new(vec![1, 2]);
+        let build = TaskId::new(vec![2]);
+        tasks.add_dependency(&build, &api).unwrap();
+        assert_eq!(tasks.get(&build).unwrap().status, TaskStatus::Blocked);
+        assert_eq!(tasks.get_blocked_tasks().map(|task| task.id().clone()).collect::<Vec<TaskId>>(), vec![build.clone()]);
+        assert_eq!(tasks.get(&TaskId::get_root_id()).unwrap().status, TaskStatus::InProgress);
+
+        // finishing the dependency unblocks it
+        tasks.set_actual_cost(&api, 1.0).unwrap();
+        assert_eq!(tasks.get(&build).unwrap().status, TaskStatus::InProgress);
+        assert_eq!(tasks.completion_percentage(), 1.0 / 3.0);
+
+        // and reopening it blocks it again
+        tasks.apply_status_updates(&HashMap::from([(api.clone(), TaskStatus::InProgress)])).unwrap();
+        assert_eq!(tasks.get(&build).unwrap().status, TaskStatus::Blocked);
+        assert_eq!(tasks.completion_percentage(), 0.0);
+
+        tasks.remove_dependency(&build, &api).unwrap();
+        assert_eq!(tasks.get(&build).unwrap().status, TaskStatus::InProgress);
+
+        let json = serde_json::to_string(&TaskStatus::Blocked).unwrap();
+        assert_eq!(serde_json::from_str::<TaskStatus>(&json).unwrap(), TaskStatus::Blocked);
+    }
+
+    #[test]
+    fn dependencies_of() {
+        let mut tasks = Tasks::new("Project");
+        let mut members = Members::new();
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+            ("", "Test"),
+            ("", "Release"),
+        ]).unwrap();
+        let (design, build, test, release) = (TaskId::new(vec![1]), TaskId::new(vec![2]), TaskId::new(vec![3]), TaskId::new(vec![4]));
+        tasks.add_dependency(&release, &test).unwrap();
+        tasks.add_dependency(&release, &design).unwrap();
+        tasks.add_dependency(&test, &build).unwrap();
+        assert_eq!(tasks.dependencies_of(&release), Some(vec![design.clone(), test.clone()]));
+        assert_eq!(tasks.dependencies_of(&design), Some(vec![]));
+        assert_eq!(tasks.dependencies_of(&TaskId::new(vec![5])), None);
+        assert_eq!(tasks.add_dependency(&build, &release), Err(Error::DependencyCycle(build.clone(), release.clone())));
+
+        // removing a task drops the edges on both sides
+        tasks.remove(&test, &mut members).unwrap();
+        assert!(tasks.is_consistent());
+        assert_eq!(tasks.dependencies_of(&TaskId::new(vec![3])), Some(vec![design.clone()]));
+        assert!(tasks.get(&build).unwrap().dependency_for.is_empty());
+    }
+
+    #[test]
+    fn rename_many() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+            ("", "Build"),
+        ]).unwrap();
+        let renames = HashMap::from([
+            (TaskId::new(vec![1]), "Entwurf".to_string()),
+            (TaskId::new(vec![1, 1]), "Schema".to_string()),
+            (TaskId::new(vec![2]), "Bau".to_string()),
+        ]);
+        assert_eq!(tasks.rename_many(&renames), Ok(2));
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().name(), "Entwurf");
+        assert_eq!(tasks.get(&TaskId::new(vec![2])).unwrap().name(), "Bau");
+
+        // all or nothing
+        let renames = HashMap::from([
+            (TaskId::new(vec![1]), "Design".to_string()),
+            (TaskId::new(vec![3]), "Release".to_string()),
+            (TaskId::new(vec![2]), " ".to_string()),
+        ]);
+        assert_eq!(tasks.rename_many(&renames), Err(Error::EmptyTaskName(TaskId::new(vec![2]))));
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().name(), "Entwurf");
+    }
+
+    #[test]
+    fn would_create_cycle() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+            ("", "Release"),
+        ]).unwrap();
+        let (design, build, release) = (TaskId::new(vec![1]), TaskId::new(vec![2]), TaskId::new(vec![3]));
+
+        // chain: release -> build -> design
+        tasks.add_dependency(&release, &build).unwrap();
+        tasks.add_dependency(&build, &design).unwrap();
+        assert!(tasks.would_create_cycle(&design, &release));
+        assert_eq!(tasks.add_dependency(&design, &release), Err(Error::DependencyCycle(design.clone(), release.clone())));
+        assert!(tasks.get(&release).unwrap().dependency_for.is_empty());
+
+        // a shortcut in the same direction is still a DAG
+        assert!(!tasks.would_create_cycle(&release, &design));
+        assert_eq!(tasks.add_dependency(&release, &design), Ok(()));
+        assert!(tasks.is_consistent());
+    }
+
+    #[test]
+    fn phase_budget() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[("", "Design")]).unwrap();
+        let design = TaskId::new(vec![1]);
+        tasks.set_planned_value(&design, 10.0).unwrap();
+        let start_date = NaiveDate::from_ymd(2022, 12, 30);
+        assert_eq!(tasks.phase_budget(&design), Err(Error::MissingDates(design.clone())));
+
+        tasks.set_start_date(&design, Some(start_date)).unwrap();
+        tasks.set_due_date(&design, Some(NaiveDate::from_ymd(2022, 12, 29))).unwrap();
+        assert_eq!(tasks.phase_budget(&design), Err(Error::InvalidDateRange(design.clone())));
+
+        tasks.set_due_date(&design, Some(NaiveDate::from_ymd(2023, 1, 3))).unwrap();
+        let budget = tasks.phase_budget(&design).unwrap();
+        assert_eq!(budget.len(), 5);
+        assert_eq!(budget[0], (start_date, 2.0));
+        assert_eq!(budget[4], (NaiveDate::from_ymd(2023, 1, 3), 2.0));
+        assert_eq!(budget.iter().map(|(_, value)| value).sum::<f64>(), 10.0);
+    }
+
+    #[test]
+    fn topological_order() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+            ("", "Docs"),
+            ("", "Release"),
+        ]).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![4]), &TaskId::new(vec![2])).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![4]), &TaskId::new(vec![3])).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![2]), &TaskId::new(vec![1, 2])).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![1, 1]), &TaskId::new(vec![1, 2])).unwrap();
+
+        assert_eq!(tasks.topological_order(), Ok(vec![
+            TaskId::new(vec![1, 2]),
+            TaskId::new(vec![1, 1]),
+            TaskId::new(vec![2]),
+            TaskId::new(vec![3]),
+            TaskId::new(vec![4]),
+        ]));
+
+        // cycles can't be added through `add_dependency`
+        tasks.get_mut(&TaskId::new(vec![1, 2])).unwrap().dependencies.insert(TaskId::new(vec![4]));
+        tasks.get_mut(&TaskId::new(vec![4])).unwrap().dependency_for.insert(TaskId::new(vec![1, 2]));
+        assert_eq!(
+            tasks.topological_order(),
+            Err(Error::DependencyCycle(TaskId::new(vec![2]), TaskId::new(vec![1, 2])))
+        );
+    }
+
+    #[test]
+    fn unestimated_branches() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+                ("2", "Backend"),
+                ("2", "Frontend"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 1]), 2.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 2]), 3.0).unwrap();
+
+        assert_eq!(tasks.unestimated_branches(), vec![TaskId::new(vec![2])]);
+
+        // estimates taken back, leaving rounding errors in the roll-up
+        tasks.set_planned_value(&TaskId::new(vec![1, 1]), 0.1).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 2]), 0.2).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 1]), 0.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 2]), 0.0).unwrap();
+        assert_eq!(tasks.unestimated_branches(), vec![TaskId::get_root_id(), TaskId::new(vec![1]), TaskId::new(vec![2])]);
+
+        // estimates that cancel out
+        tasks.set_planned_value(&TaskId::new(vec![2, 1]), 5.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2, 2]), -5.0).unwrap();
+        assert_eq!(tasks.unestimated_branches(), vec![TaskId::new(vec![1])]);
+    }
+
+    #[test]
+    fn critical_path() {
+        let mut tasks = Tasks::new("Project");
+        assert_eq!(tasks.critical_path(), Ok(vec![]));
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Backend"),
+            ("", "Frontend"),
+            ("", "Release"),
+            ("", "Docs"),
+        ]).unwrap();
+        let ids = (1..=5).map(|idx| TaskId::new(vec![idx])).collect::<Vec<TaskId>>();
+        [2.0, 5.0, 3.0, 1.0, 8.0].iter().zip(&ids).for_each(|(planned_value, id)| {
+            tasks.set_planned_value(id, *planned_value).unwrap();
+        });
+        tasks.add_dependency(&ids[1], &ids[0]).unwrap();
+        tasks.add_dependency(&ids[2], &ids[0]).unwrap();
+        tasks.add_dependency(&ids[3], &ids[1]).unwrap();
+        tasks.add_dependency(&ids[3], &ids[2]).unwrap();
+
+        // design -> backend -> release weighs 8, same as docs alone, but comes first
+        assert_eq!(tasks.critical_path(), Ok(vec![ids[0].clone(), ids[1].clone(), ids[3].clone()]));
+        tasks.set_planned_value(&ids[4], 9.0).unwrap();
+        assert_eq!(tasks.critical_path(), Ok(vec![ids[4].clone()]));
+    }
+
+    #[test]
+    fn minimize_dependencies() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+            ("", "Test"),
+            ("", "Release"),
+        ]).unwrap();
+        let (design, build, test, release) = (TaskId::new(vec![1]), TaskId::new(vec![2]), TaskId::new(vec![3]), TaskId::new(vec![4]));
+        tasks.add_dependency(&release, &test).unwrap();
+        tasks.add_dependency(&test, &build).unwrap();
+        tasks.add_dependency(&build, &design).unwrap();
+        tasks.add_dependency(&release, &build).unwrap();
+        tasks.add_dependency(&release, &design).unwrap();
+        tasks.add_dependency(&test, &design).unwrap();
+
+        assert_eq!(tasks.minimize_dependencies(), Ok(3));
+        assert!(tasks.is_consistent());
+        assert_eq!(tasks.dependencies_of(&release), Some(vec![test.clone()]));
+        assert_eq!(tasks.dependencies_of(&test), Some(vec![build.clone()]));
+        assert_eq!(tasks.dependencies_of(&build), Some(vec![design.clone()]));
+        assert_eq!(tasks.minimize_dependencies(), Ok(0));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 1]), 2.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 3.0).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![2]), &TaskId::new(vec![1, 2])).unwrap();
+
+        tasks.set_target(Some(10.0));
+        let loaded = Tasks::from_json(&tasks.to_json().unwrap()).unwrap();
+        assert_eq!(loaded.store, tasks.store);
+        // settings stay in project files
+        assert_eq!((loaded.target(), loaded.earned_value_mode()), (None, EvMode::LeafSum));
+        assert_eq!(loaded.to_tree_str_with_values(), tasks.to_tree_str_with_values());
+        assert_eq!((loaded.leaf_count(), loaded.done_count()), (tasks.leaf_count(), tasks.done_count()));
+
+        // the root claims a child that isn't there
+        let root = tasks.get(&TaskId::get_root_id()).unwrap();
+        let json = serde_json::to_string(&vec![root]).unwrap();
+        assert_eq!(Tasks::from_json(&json).unwrap_err(), Error::MissingChild(TaskId::get_root_id(), TaskId::new(vec![1])));
+        assert!(matches!(Tasks::from_json("[{"), Err(Error::ParseJsonContents(_))));
+    }
+
+    #[test]
+    fn save_and_load_file() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+        ]).unwrap();
+        let dir = std::env::temp_dir().join(format!("aplan-save-and-load-file-{}", std::process::id()));
+        let path = dir.join("nested").join("tasks.json");
+
+        tasks.save_to_file(&path).unwrap();
+        let loaded = Tasks::load_from_file(&path).unwrap();
+        assert_eq!(loaded.to_tree_str(), tasks.to_tree_str());
+
+        let json = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, &json[..json.len() / 2]).unwrap();
+        assert!(matches!(Tasks::load_from_file(&path), Err(Error::ParseJsonContents(_))));
+        let missing = dir.join("missing.json");
+        assert_eq!(Tasks::load_from_file(&missing).unwrap_err(), Error::FileRead(missing.display().to_string()));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn aggregate_to_depth() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+                ("2", "Backend"),
+                    ("2.1", "Database"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 1]), 2.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 2]), 3.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2, 1, 1]), 7.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 1.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 2]), 4.0).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![2, 1, 1]), &TaskId::new(vec![1, 2])).unwrap();
+
+        let aggregated = tasks.aggregate_to_depth(1);
+        assert!(aggregated.is_consistent());
+        assert_eq!(aggregated.len(), 3);
+        assert_eq!(aggregated.leaf_count(), 2);
+        let design = aggregated.get(&TaskId::new(vec![1])).unwrap();
+        assert!(design.is_leaf());
+        assert_eq!((design.get_planned_value(), design.get_actual_cost(), &design.status), (5.0, 5.0, &TaskStatus::Done));
+        let build = aggregated.get(&TaskId::new(vec![2])).unwrap();
+        assert_eq!((build.get_planned_value(), build.get_actual_cost(), &build.status), (7.0, 0.0, &TaskStatus::InProgress));
+        assert_eq!(aggregated.planned_value(), tasks.planned_value());
+        aggregated.debug_assert_rollups();
+    }
+
+    #[test]
+    fn dot_str_escaping() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Say \"hi\""),
+            ("", "C:\\temp"),
+        ]).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![2]), &TaskId::new(vec![1])).unwrap();
+
+        let dot = tasks.to_dot_str();
+        assert!(dot.contains("\t\" - Project ✗\\npv: 0 ac: 0\" -> \"1 - Say \\\"hi\\\" ✗\\npv: 0 ac: 0\" [style=solid"));
+        assert!(dot.contains("\"2 - C:\\\\temp ⊘\\npv: 0 ac: 0\" -> \"1 - Say \\\"hi\\\" ✗\\npv: 0 ac: 0\" [style=dashed"));
+        // every edge line is exactly two quoted node names and the attributes
+        dot.lines().filter(|line| line.contains("->")).for_each(|line| {
+            assert_eq!(line.replace("\\\\", "").replace("\\\"", "").matches('"').count(), 4, "{}", line);
+        });
+        assert!(tasks.to_dependency_dot_str().contains("\t\"1\" [label=\"1 - Say \\\"hi\\\"\", color=black];\n"));
+    }
+
+    #[test]
+    fn changed_subtrees() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+                ("2", "Backend"),
+        ]).unwrap();
+        let hashes = tasks.subtree_hashes();
+        assert_eq!(hashes[&TaskId::new(vec![1])], tasks.subtree_hash(&TaskId::new(vec![1])).unwrap());
+        assert!(tasks.changed_subtrees(&hashes).is_empty());
+
+        tasks.set_planned_value(&TaskId::new(vec![1, 2]), 3.0).unwrap();
+        assert_eq!(tasks.changed_subtrees(&hashes), vec![
+            TaskId::get_root_id(),
+            TaskId::new(vec![1]),
+            TaskId::new(vec![1, 2]),
+        ]);
+    }
+
+    #[test]
+    fn dot_str_large_tree() {
+        let mut tasks = Tasks::with_capacity("Project", 1000);
+        for phase in 1..=10 {
+            tasks.add_task(TaskId::get_root_id(), &format!("Phase {}", phase)).unwrap();
+            for step in 1..=99 {
+                tasks.add_task(TaskId::new(vec![phase]), &format!("Step {}", step)).unwrap();
+            }
+        }
+        assert_eq!(tasks.len(), 1001);
+
+        let dot = tasks.to_dot_str();
+        assert!(dot.starts_with("digraph G {\n"));
+        assert!(dot.ends_with("}"));
+        assert_eq!(dot.lines().filter(|line| line.contains("style=solid")).count(), 1000);
+    }
+
+    #[test]
+    fn earned_value_modes() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+        ]).unwrap();
+        let design = TaskId::new(vec![1]);
+        tasks.set_planned_value(&TaskId::new(vec![1, 1]), 2.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 2]), 3.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 2.0).unwrap();
+
+        assert_eq!(tasks.earned_value_mode(), EvMode::LeafSum);
+        assert_eq!(tasks.subtree_earned_value(&design), Ok(2.0));
+        tasks.set_earned_value_mode(EvMode::MilestoneOnTrunk);
+        assert_eq!(tasks.subtree_earned_value(&design), Ok(0.0));
+        assert_eq!(tasks.subtree_earned_value(&TaskId::new(vec![1, 1])), Ok(2.0));
+
+        tasks.set_actual_cost(&TaskId::new(vec![1, 2]), 3.0).unwrap();
+        assert_eq!(tasks.subtree_earned_value(&design), Ok(5.0));
+    }
+
+    #[test]
+    fn earned_value_mode_in_metrics() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+        ]).unwrap();
+        let design = TaskId::new(vec![1]);
+        tasks.set_planned_value(&TaskId::new(vec![1, 1]), 2.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 2]), 3.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2]), 5.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 2.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![2]), 5.0).unwrap();
+
+        assert_eq!(tasks.earned_value(), 7.0);
+        assert_eq!(tasks.metrics_for(&design).unwrap().earned_value, 2.0);
+        tasks.set_earned_value_mode(EvMode::MilestoneOnTrunk);
+        // Design is half done, so it earns nothing yet, unlike the Build leaf
+        assert_eq!(tasks.earned_value(), 5.0);
+        assert_eq!(tasks.spi(), 0.5);
+        assert_eq!(tasks.cpi(), 5.0 / 7.0);
+        assert_eq!(tasks.metrics_for(&design).unwrap().earned_value, 0.0);
+        assert!(tasks.summary_json().contains("\"earned_value\":5.0"));
+    }
+
+    #[test]
+    fn tree_str_colored() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+            ("", "Build"),
+            ("", "Test"),
+        ]).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 1.0).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![3]), &TaskId::new(vec![2])).unwrap();
+
+        let colored = tasks.to_tree_str_colored(true);
+        let lines = colored.lines().collect::<Vec<&str>>();
+        assert!(lines[2].contains("\x1b[32m1.1 - Schema ✔"));
+        assert!(lines[1].contains("\x1b[2;32m1 - Design"));
+        assert!(lines[3].ends_with("2 - Build ✗ -> []"));
+        assert!(lines[4].contains("\x1b[31m3 - Test"));
+        assert_eq!(tasks.to_tree_str_colored(false), tasks.to_tree_str());
+    }
+
+    #[test]
+    fn tasks_in_id_order() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+            ("", "Test"),
+        ]).unwrap();
+        (0..10).for_each(|_| { tasks.add_task(TaskId::new(vec![3]), "Case").unwrap(); });
+        tasks.set_actual_cost(&TaskId::new(vec![1, 2]), 1.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![3, 10]), 1.0).unwrap();
+
+        fn ids<'a>(tasks: impl Iterator<Item=&'a Task>) -> Vec<String> {
+            tasks.map(|task| task.id().to_string()).collect()
+        }
+        let first = ids(tasks.get_tasks());
+        assert_eq!(first, ids(tasks.clone().get_tasks()));
+        assert_eq!(&first[..5], ["1.1", "1.2", "2", "3.1", "3.2"]);
+        assert_eq!(first.last().unwrap(), "3.10");
+        assert_eq!(ids(tasks.get_done_tasks()), ["1.2", "3.10"]);
+        assert_eq!(ids(tasks.get_todo_tasks()).len(), 11);
+        assert!(TaskId::new(vec![3, 2]) < TaskId::new(vec![3, 10]));
+        assert!(TaskId::new(vec![1, 2]) < TaskId::new(vec![2]));
+    }
+
+    #[test]
+    fn chain_dependencies() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+            ("", "Test"),
+        ]).unwrap();
+        let design = TaskId::new(vec![1]);
+        let build = TaskId::new(vec![2]);
+        let test = TaskId::new(vec![3]);
+
+        tasks.chain_dependencies(&[design.clone(), build.clone(), test.clone()]).unwrap();
+        assert_eq!(tasks.dependencies_of(&design), Some(vec![]));
+        assert_eq!(tasks.dependencies_of(&build), Some(vec![design.clone()]));
+        assert_eq!(tasks.dependencies_of(&test), Some(vec![build.clone()]));
+
+        // closing the loop fails without leaving any edge behind
+        tasks.remove_dependency(&test, &build).unwrap();
+        assert_eq!(
+            tasks.chain_dependencies(&[build.clone(), test.clone(), design.clone()]),
+            Err(Error::DependencyCycle(design.clone(), test.clone()))
+        );
+        assert_eq!(tasks.dependencies_of(&test), Some(vec![]));
+        assert_eq!(
+            tasks.chain_dependencies(&[test.clone(), TaskId::new(vec![4])]),
+            Err(Error::TaskNotFound(TaskId::new(vec![4])))
+        );
+        assert_eq!(tasks.dependencies_of(&TaskId::new(vec![4])), None);
+    }
+
+    #[test]
+    fn slack_report() {
+        let mut tasks = Tasks::new("Project");
+        assert_eq!(tasks.slack_report(), Ok(vec![]));
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Backend"),
+            ("", "Frontend"),
+            ("", "Release"),
+            ("", "Docs"),
+        ]).unwrap();
+        let ids = (1..=5).map(|idx| TaskId::new(vec![idx])).collect::<Vec<TaskId>>();
+        [2.0, 5.0, 3.0, 1.0, 4.0].iter().zip(&ids).for_each(|(planned_value, id)| {
+            tasks.set_planned_value(id, *planned_value).unwrap();
+        });
+        tasks.add_dependency(&ids[1], &ids[0]).unwrap();
+        tasks.add_dependency(&ids[2], &ids[0]).unwrap();
+        tasks.add_dependency(&ids[3], &ids[1]).unwrap();
+        tasks.add_dependency(&ids[3], &ids[2]).unwrap();
+
+        // design -> backend -> release is critical and takes 8
+        assert_eq!(tasks.slack_report(), Ok(vec![
+            (ids[0].clone(), 0.0),
+            (ids[1].clone(), 0.0),
+            (ids[3].clone(), 0.0),
+            (ids[2].clone(), 2.0),
+            (ids[4].clone(), 4.0),
+        ]));
+        assert_eq!(tasks.total_floats().unwrap().len(), 5);
+
+        tasks.set_actual_cost(&ids[0], 2.0).unwrap();
+        assert_eq!(tasks.slack_report().unwrap()[0], (ids[1].clone(), 0.0));
+    }
+
+    #[test]
+    fn ordered_store() {
+        let mut tasks = Tasks::new_ordered("Project");
+        (0..100).for_each(|_| { tasks.add_task(TaskId::get_root_id(), "Phase").unwrap(); });
+        (1..=100).for_each(|idx| (0..99).for_each(|_| {
+            tasks.add_task(TaskId::new(vec![idx]), "Step").unwrap();
+        }));
+        assert!(tasks.is_ordered());
+        assert_eq!(tasks.len(), 10_001);
+
+        // the store itself iterates in id order
+        let ids = tasks.store.values().map(|task| task.id().clone()).collect::<Vec<TaskId>>();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        let leaves = tasks.get_tasks().map(|task| task.id().clone()).collect::<Vec<TaskId>>();
+        assert_eq!(leaves.len(), 9_900);
+        assert!(leaves.windows(2).all(|pair| pair[0] < pair[1]));
+
+        // same answers as the hashed store
+        let mut hashed = Tasks::new("Project");
+        assert!(!hashed.is_ordered());
+        hashed.expand(&[("", "Design"), ("1", "Schema"), ("1", "API"), ("", "Build")]).unwrap();
+        let mut ordered = Tasks::new_ordered("Project");
+        ordered.expand(&[("", "Design"), ("1", "Schema"), ("1", "API"), ("", "Build")]).unwrap();
+        ordered.set_actual_cost(&TaskId::new(vec![1, 2]), 1.0).unwrap();
+        hashed.set_actual_cost(&TaskId::new(vec![1, 2]), 1.0).unwrap();
+        ordered.remove(&TaskId::new(vec![1, 1]), &mut Members::new()).unwrap();
+        hashed.remove(&TaskId::new(vec![1, 1]), &mut Members::new()).unwrap();
+        assert_eq!(ordered.store, hashed.store);
+        assert_eq!(ordered.to_tree_str(), hashed.to_tree_str());
+        assert!(ordered.aggregate_to_depth(1).is_ordered());
+    }
+
+    #[test]
+    fn max_dependency_depth() {
+        let mut tasks = Tasks::new("Project");
+        assert_eq!(tasks.max_dependency_depth(), Ok(0));
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+            ("", "Test"),
+            ("", "Release"),
+            ("", "Docs"),
+        ]).unwrap();
+        assert_eq!(tasks.max_dependency_depth(), Ok(0));
+        let ids = (1..=5).map(|idx| TaskId::new(vec![idx])).collect::<Vec<TaskId>>();
+        tasks.chain_dependencies(&ids[..4]).unwrap();
+        tasks.add_dependency(&ids[4], &ids[0]).unwrap();
+        assert_eq!(tasks.max_dependency_depth(), Ok(3));
+    }
+
+    #[test]
+    fn recompute_aggregates() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+                    ("1.2", "Routes"),
+            ("", "Build"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 1]), 2.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 2, 1]), 3.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2]), 4.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 1.0).unwrap();
+
+        tasks.get_mut(&TaskId::new(vec![1, 2])).unwrap().planned_value = 10.0;
+        tasks.get_mut(&TaskId::new(vec![1])).unwrap().actual_cost = 7.0;
+        tasks.get_mut(&TaskId::get_root_id()).unwrap().planned_value = 0.0;
+        tasks.recompute_aggregates();
+        tasks.debug_assert_rollups();
+        assert_eq!(tasks.get(&TaskId::new(vec![1, 2])).unwrap().get_planned_value(), 3.0);
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().get_planned_value(), 5.0);
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().get_actual_cost(), 1.0);
+        assert_eq!(tasks.planned_value(), 9.0);
+        assert_eq!(tasks.actual_cost(), 1.0);
+    }
+
+    #[test]
+    fn unchanged_values() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+        ]).unwrap();
+        let schema = TaskId::new(vec![1, 1]);
+        let date = NaiveDate::from_ymd(2022, 10, 3);
+
+        // a zero cost still marks a new task as done
+        tasks.set_actual_cost(&schema, 0.0).unwrap();
+        assert_eq!(tasks.get(&schema).unwrap().status, TaskStatus::Done);
+        tasks.set_done_date(&schema, date).unwrap();
+
+        // while setting the same values again leaves everything as it was
+        let before = tasks.clone();
+        tasks.set_actual_cost(&schema, 0.0).unwrap();
+        assert_eq!(tasks.set_planned_value(&schema, 0.0), Ok(0.0));
+        assert_eq!(tasks.store, before.store);
+        assert_eq!(tasks.get(&schema).unwrap().done_date(), Some(date));
+        assert_eq!(
+            tasks.set_planned_value(&TaskId::new(vec![1]), 0.0),
+            Err(Error::TrunkCannotChangeValue(TaskId::new(vec![1]))));
+    }
+
+    #[test]
+    fn spi_contributors() {
+        let mut tasks = Tasks::new("Project");
+        assert_eq!(tasks.spi_contributors(), vec![]);
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+            ("", "Release"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 1]), 9.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 2]), 2.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2]), 5.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![3]), 2.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1, 1]), 9.0).unwrap();
+
+        assert_eq!(tasks.spi_contributors(), vec![
+            (TaskId::new(vec![2]), 5.0),
+            (TaskId::new(vec![1, 2]), 2.0),
+            (TaskId::new(vec![3]), 2.0),
+        ]);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn value_completion_percentage() {
+        let mut tasks = Tasks::new("Project");
+        tasks.add_task(TaskId::get_root_id(), "Platform").unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1]), 90.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1]), 80.0).unwrap();
+        (2..=10).for_each(|idx| {
+            tasks.add_task(TaskId::get_root_id(), "Chore").unwrap();
+            tasks.set_planned_value(&TaskId::new(vec![idx]), 1.0).unwrap();
+        });
+
+        assert_eq!(tasks.value_completion_percentage(), 90.0 / 99.0);
+        assert_eq!(tasks.completion_percentage(), 0.1);
+        assert_eq!(tasks.earned_value(), 90.0);
+        assert_eq!(tasks.spi(), tasks.spi_weighted());
+    }
+
+    #[test]
+    fn budget_variance() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1]), 3.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2]), 5.0).unwrap();
+        assert_eq!(tasks.budget_variance(), None);
+
+        tasks.set_target(Some(10.0));
+        assert_eq!(tasks.target(), Some(10.0));
+        assert_eq!(tasks.budget_variance(), Some(2.0));
+        tasks.set_planned_value(&TaskId::new(vec![2]), 9.0).unwrap();
+        assert_eq!(tasks.budget_variance(), Some(-2.0));
+        assert_eq!(tasks.aggregate_to_depth(0).budget_variance(), Some(-2.0));
+    }
+
+    #[test]
+    fn forecasts() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1]), 4.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2]), 6.0).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1]), 5.0).unwrap();
+
+        // cpi = 4 / 5
+        assert_eq!(tasks.eac(), 12.5);
+        assert_eq!(tasks.vac(), -2.5);
+        assert_eq!(tasks.tcpi(), 6.0 / 5.0);
+        assert!(tasks.to_dot_str().contains(", eac: 12.5, vac: -2.5, tcpi: 1.2\""));
+
+        // forecasts are made against the target once there's one
+        tasks.set_target(Some(20.0));
+        assert_eq!(tasks.budget_at_completion(), 20.0);
+        assert_eq!(tasks.eac(), 25.0);
+        assert_eq!(tasks.vac(), -5.0);
+        assert_eq!(tasks.tcpi(), 16.0 / 15.0);
+        assert_eq!(tasks.metrics_for(&TaskId::get_root_id()), Ok(tasks.metrics()));
+        tasks.set_target(None);
+
+        // the whole budget already spent
+        tasks.set_actual_cost(&TaskId::new(vec![1]), 10.0).unwrap();
+        assert_eq!(tasks.tcpi(), 0.0);
+    }
+
+    #[test]
+    fn reverse_topological_order() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+            ("", "Test"),
+        ]).unwrap();
+        let ids = (1..=3).map(|idx| TaskId::new(vec![idx])).collect::<Vec<TaskId>>();
+        tasks.chain_dependencies(&[ids[1].clone(), ids[0].clone(), ids[2].clone()]).unwrap();
+
+        assert_eq!(tasks.topological_order(), Ok(vec![ids[1].clone(), ids[0].clone(), ids[2].clone()]));
+        assert_eq!(tasks.reverse_topological_order(), Ok(vec![ids[2].clone(), ids[0].clone(), ids[1].clone()]));
+    }
+
+    #[test]
+    fn remove_subtree() {
+        let mut tasks = Tasks::new("Project");
+        let mut members = Members::new();
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+                ("2", "Backend"),
+                    ("2.1", "Routes"),
+                ("2", "Frontend"),
+            ("", "Test"),
+            ("", "Release"),
+        ]).unwrap();
+        let (design, build, test, release) = (TaskId::new(vec![1]), TaskId::new(vec![2]), TaskId::new(vec![3]), TaskId::new(vec![4]));
+        tasks.set_planned_value(&TaskId::new(vec![2, 1, 1]), 2.0).unwrap();
+        tasks.set_planned_value(&test, 3.0).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![2, 1, 1]), &design).unwrap();
+        tasks.add_dependency(&test, &TaskId::new(vec![2, 2])).unwrap();
+        tasks.add_dependency(&release, &test).unwrap();
+        members.insert("alice".to_string()).unwrap();
+        members.assign_task_to_member(TaskId::new(vec![2, 2]), "alice", &mut tasks).unwrap();
+
+        assert_eq!(tasks.remove_subtree(&build, &mut members), Err(Error::CannotRemoveAssignedTask(TaskId::new(vec![2, 2]))));
+        assert_eq!(tasks.len(), 8);
+        members.remove_member_from_task(&TaskId::new(vec![2, 2]), "alice", &mut tasks).unwrap();
+
+        let removed = tasks.remove_subtree(&build, &mut members).unwrap();
+        assert_eq!(removed.iter().map(|task| task.name()).collect::<Vec<&str>>(), ["Build", "Backend", "Routes", "Frontend"]);
+        assert!(tasks.is_consistent());
+        tasks.debug_assert_rollups();
+        assert_eq!(tasks.len(), 4);
+        assert_eq!(tasks.planned_value(), 3.0);
+        // "Test" and "Release" moved up, keeping the edge between them
+        assert_eq!(tasks.get(&build).unwrap().name(), "Test");
+        assert_eq!(tasks.dependencies_of(&build), Some(vec![]));
+        assert_eq!(tasks.dependencies_of(&test), Some(vec![build.clone()]));
+        assert!(tasks.get(&design).unwrap().dependency_for.is_empty());
+    }
+
+    #[test]
+    fn overdue_tasks() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+            ("", "Test"),
+            ("", "Release"),
+        ]).unwrap();
+        let today = NaiveDate::from_ymd(2022, 10, 10);
+        tasks.set_due_date(&TaskId::new(vec![1]), Some(NaiveDate::from_ymd(2022, 10, 3))).unwrap();
+        tasks.set_due_date(&TaskId::new(vec![2]), Some(NaiveDate::from_ymd(2022, 10, 7))).unwrap();
+        tasks.set_due_date(&TaskId::new(vec![3]), Some(today)).unwrap();
+        tasks.set_actual_cost(&TaskId::new(vec![1]), 1.0).unwrap();
+
+        // "Design" is done, "Test" is due today and "Release" has no due date
+        let overdue = tasks.get_overdue_tasks(today).map(|task| task.id().clone()).collect::<Vec<TaskId>>();
+        assert_eq!(overdue, vec![TaskId::new(vec![2])]);
+
+        // project files from before due dates still load
+        let json = r#"{"id":{"id":[1]},"name":"Design","status":"InProgress","planned_value":0.0,"actual_cost":0.0,"num_child":0,"dependencies":[],"dependency_for":[]}"#;
+        assert_eq!(serde_json::from_str::<Task>(json).unwrap().due_date(), None);
+    }
+
+    #[test]
+    fn apply_actual_costs() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+        ]).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![2]), &TaskId::new(vec![1, 2])).unwrap();
+
+        let costs = HashMap::from([
+            (TaskId::new(vec![1, 1]), 2.0),
+            (TaskId::new(vec![1, 2]), 3.5),
+            (TaskId::new(vec![1]), 1.0),
+            (TaskId::new(vec![4]), 1.0),
+        ]);
+        assert_eq!(tasks.apply_actual_costs(&costs), Ok(vec![TaskId::new(vec![1]), TaskId::new(vec![4])]));
+        tasks.debug_assert_rollups();
+        assert_eq!(tasks.actual_cost(), 5.5);
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().get_actual_cost(), 5.5);
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().status, TaskStatus::Done);
+        assert_eq!(tasks.get(&TaskId::new(vec![2])).unwrap().status, TaskStatus::InProgress);
+        assert_eq!(tasks.done_count(), 2);
+    }
+
+    #[test]
+    fn planned_value_curve() {
+        let mut tasks = Tasks::new("Project");
+        assert_eq!(tasks.planned_value_curve(), (vec![], 0.0));
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "API"),
+            ("", "Build"),
+            ("", "Docs"),
+        ]).unwrap();
+        let (early, late) = (NaiveDate::from_ymd(2022, 10, 3), NaiveDate::from_ymd(2022, 10, 17));
+        [(vec![1, 1], 2.0, Some(late)), (vec![1, 2], 3.0, Some(early)), (vec![2], 5.0, Some(late)), (vec![3], 1.5, None)]
+            .into_iter()
+            .for_each(|(id, planned_value, due_date)| {
+                let id = TaskId::new(id);
+                tasks.set_planned_value(&id, planned_value).unwrap();
+                tasks.set_due_date(&id, due_date).unwrap();
+            });
+        // a trunk's due date doesn't count
+        tasks.set_due_date(&TaskId::new(vec![1]), Some(early)).unwrap();
+
+        assert_eq!(tasks.planned_value_curve(), (vec![(early, 3.0), (late, 10.0)], 1.5));
+    }
+
+    #[test]
+    fn partition_by_member() {
+        let mut tasks = Tasks::new("Project");
+        let mut members = Members::new();
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+            ("", "Docs"),
+        ]).unwrap();
+        ["alice", "bob", "carol"].iter().for_each(|name| members.insert(name.to_string()).unwrap());
+        members.assign_task_to_member(TaskId::new(vec![2]), "alice", &mut tasks).unwrap();
+        members.assign_task_to_member(TaskId::new(vec![1]), "alice", &mut tasks).unwrap();
+        members.assign_task_to_member(TaskId::new(vec![2]), "bob", &mut tasks).unwrap();
+
+        assert_eq!(tasks.partition_by_member(&members), HashMap::from([
+            ("alice".to_string(), vec![TaskId::new(vec![1]), TaskId::new(vec![2])]),
+            ("bob".to_string(), vec![TaskId::new(vec![2])]),
+        ]));
+    }
+
+    #[test]
+    fn task_members() {
+        let mut tasks = Tasks::new("Project");
+        let mut members = Members::new();
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+            ("", "Build"),
+        ]).unwrap();
+        let (schema, build) = (TaskId::new(vec![1, 1]), TaskId::new(vec![2]));
+        members.insert("alice".to_string()).unwrap();
+        members.insert("bob".to_string()).unwrap();
+        members.assign_task_to_member(schema.clone(), "alice", &mut tasks).unwrap();
+        members.assign_task_to_member(schema.clone(), "bob", &mut tasks).unwrap();
+        members.assign_task_to_member(build.clone(), "bob", &mut tasks).unwrap();
+
+        let mut names = tasks.get(&schema).unwrap().members().collect::<Vec<&str>>();
+        names.sort();
+        assert_eq!(names, ["alice", "bob"]);
+        // members are assigned to leaves only, trunks don't collect them
+        assert_eq!(
+            members.assign_task_to_member(TaskId::new(vec![1]), "alice", &mut tasks),
+            Err(Error::TrunkCannotAddMember(TaskId::new(vec![1]))));
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().members().count(), 0);
+
+        members.remove_member_from_task(&schema, "alice", &mut tasks).unwrap();
+        assert!(!tasks.get(&schema).unwrap().has_member("alice"));
+        assert!(tasks.get(&schema).unwrap().has_member("bob"));
+
+        // they move along with their task
+        tasks.reparent(&build, &TaskId::new(vec![1]), false, &mut members).unwrap();
+        assert!(tasks.get(&TaskId::new(vec![1, 2])).unwrap().has_member("bob"));
+        assert!(members.get("bob").unwrap().is_assigned_to(&TaskId::new(vec![1, 2])));
+
+        members.remove_member("bob", &mut tasks).unwrap();
+        assert_eq!(tasks.get(&schema).unwrap().members().count(), 0);
+        assert_eq!(tasks.get(&TaskId::new(vec![1, 2])).unwrap().members().count(), 0);
+    }
+
+    #[test]
+    fn member_workload() {
+        let mut tasks = Tasks::new("Project");
+        let mut members = Members::new();
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+            ("", "Docs"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1]), 2.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2]), 5.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![3]), 1.0).unwrap();
+        members.insert("alice".to_string()).unwrap();
+        members.assign_task_to_member(TaskId::new(vec![2]), "alice", &mut tasks).unwrap();
+        members.assign_task_to_member(TaskId::new(vec![1]), "alice", &mut tasks).unwrap();
+
+        let ids = tasks.tasks_for_member("alice").map(|task| task.id().clone()).collect::<Vec<TaskId>>();
+        assert_eq!(ids, vec![TaskId::new(vec![1]), TaskId::new(vec![2])]);
+        assert_eq!(tasks.member_workload("alice"), 7.0);
+        assert_eq!(tasks.member_workload("bob"), 0.0);
+    }
+
+    #[test]
+    fn all_members() {
+        let mut tasks = Tasks::new("Project");
+        let mut members = Members::new();
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+            ("", "Build"),
+        ]).unwrap();
+        assert!(tasks.all_members().is_empty());
+        for name in ["carol", "alice", "bob"] {
+            members.insert(name.to_string()).unwrap();
+        }
+        members.assign_task_to_member(TaskId::new(vec![1, 1]), "carol", &mut tasks).unwrap();
+        members.assign_task_to_member(TaskId::new(vec![1, 1]), "alice", &mut tasks).unwrap();
+        members.assign_task_to_member(TaskId::new(vec![2]), "alice", &mut tasks).unwrap();
+
+        assert_eq!(tasks.all_members().into_iter().collect::<Vec<String>>(), vec!["alice", "carol"]);
+    }
+
+    #[test]
+    fn renumber_batch() {
+        let mut tasks = Tasks::new("Project");
+        let mut members = Members::new();
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+                ("2", "Backend"),
+                ("2", "Frontend"),
+                ("2", "Mobile"),
+            ("", "Test"),
+            ("", "Release"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![2, 3]), 2.0).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![4]), 3.0).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![4]), &TaskId::new(vec![2, 3])).unwrap();
+        members.insert("alice".to_string()).unwrap();
+        members.assign_task_to_member(TaskId::new(vec![4]), "alice", &mut tasks).unwrap();
+
+        assert_eq!(
+            tasks.renumber_batch(&[TaskId::new(vec![1]), TaskId::new(vec![4])], &mut members),
+            Err(Error::CannotRemoveAssignedTask(TaskId::new(vec![4]))));
+        assert_eq!(
+            tasks.renumber_batch(&[TaskId::new(vec![1]), TaskId::new(vec![2])], &mut members),
+            Err(Error::TrunkCannotBeRemoved(TaskId::new(vec![2]))));
+        assert_eq!(tasks.len(), 8);
+
+        // removing one of these at a time would shift the other before it's reached
+        let mapping = tasks.renumber_batch(&[TaskId::new(vec![3]), TaskId::new(vec![1]), TaskId::new(vec![2, 1])], &mut members).unwrap();
+        assert_eq!(mapping.len(), 4);
+        assert_eq!(mapping[&TaskId::new(vec![2, 3])], TaskId::new(vec![1, 2]));
+        assert_eq!(mapping[&TaskId::new(vec![4])], TaskId::new(vec![2]));
+        assert!(tasks.is_consistent());
+        tasks.debug_assert_rollups();
+        assert_eq!(
+            tasks.dfs_ids().iter().map(|id| (id.to_string(), tasks.get(id).unwrap().name())).collect::<Vec<(String, &str)>>(),
+            [("", "Project"), ("1", "Build"), ("1.1", "Frontend"), ("1.2", "Mobile"), ("2", "Release")]
+                .map(|(id, name)| (id.to_string(), name)));
+        assert_eq!(tasks.dependencies_of(&TaskId::new(vec![2])), Some(vec![TaskId::new(vec![1, 2])]));
+        assert_eq!(tasks.planned_value(), 5.0);
+        assert!(members.get("alice").unwrap().is_assigned_to(&TaskId::new(vec![2])));
+    }
+
+    #[test]
+    fn rename_task() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+            ("", "Build"),
+        ]).unwrap();
+        tasks.rename_task(&TaskId::new(vec![1, 1]), "Data model").unwrap();
+        tasks.rename_task(&TaskId::get_root_id(), "Website").unwrap();
+        assert_eq!(tasks.name(), "Website");
+        let tree = tasks.to_tree_str();
+        assert!(tree.contains("Website"));
+        assert!(tree.contains("1.1 - Data model"));
+        assert!(!tree.contains("Schema"));
+
+        assert_eq!(tasks.rename_task(&TaskId::new(vec![3]), "Release"), Err(Error::TaskNotFound(TaskId::new(vec![3]))));
+        assert_eq!(tasks.rename_task(&TaskId::new(vec![2]), ""), Err(Error::EmptyTaskName(TaskId::new(vec![2]))));
+        assert_eq!(tasks.get(&TaskId::new(vec![2])).unwrap().name(), "Build");
+    }
+
+    #[test]
+    fn description() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+        ]).unwrap();
+        assert!(!tasks.to_dot_str().contains("tooltip"));
+
+        tasks.set_description(&TaskId::new(vec![1]), Some("Wireframes \"and\" mockups")).unwrap();
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().description(), Some("Wireframes \"and\" mockups"));
+        let dot = tasks.to_dot_str();
+        assert_eq!(dot.matches("tooltip").count(), 1);
+        assert!(dot.contains("\t\"1 - Design ✗\\npv: 0 ac: 0\" [tooltip=\"Wireframes \\\"and\\\" mockups\"]\n"));
+
+        tasks.set_description(&TaskId::new(vec![1]), None).unwrap();
+        assert!(!tasks.to_dot_str().contains("tooltip"));
+        assert_eq!(tasks.set_description(&TaskId::new(vec![3]), Some("Release")), Err(Error::TaskNotFound(TaskId::new(vec![3]))));
+
+        // project files from before descriptions still load
+        let json = r#"{"id":{"id":[1]},"name":"Design","status":"InProgress","planned_value":0.0,"actual_cost":0.0,"num_child":0,"dependencies":[],"dependency_for":[]}"#;
+        assert_eq!(serde_json::from_str::<Task>(json).unwrap().description(), None);
+    }
+
+    #[test]
+    fn mark_done() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "Mockups"),
+            ("", "Build"),
+        ]).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![2]), &TaskId::new(vec![1, 2])).unwrap();
+        assert_eq!(tasks.mark_done(&TaskId::new(vec![1])), Err(Error::CannotMarkTrunk(TaskId::new(vec![1]))));
+
+        tasks.mark_done(&TaskId::new(vec![1, 1])).unwrap();
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().status, TaskStatus::InProgress);
+        tasks.mark_done(&TaskId::new(vec![1, 2])).unwrap();
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().status, TaskStatus::Done);
+        assert_eq!(tasks.get(&TaskId::new(vec![2])).unwrap().status, TaskStatus::InProgress);
+        assert_eq!(tasks.get(&TaskId::get_root_id()).unwrap().status, TaskStatus::InProgress);
+        assert_eq!(tasks.actual_cost(), 0.0);
+        assert_eq!(tasks.completion_percentage(), 2.0 / 3.0);
+
+        tasks.mark_done(&TaskId::new(vec![2])).unwrap();
+        assert_eq!(tasks.get(&TaskId::get_root_id()).unwrap().status, TaskStatus::Done);
+        tasks.debug_assert_rollups();
+    }
+
+    #[test]
+    fn reopen() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                    ("1.1", "Tables"),
+            ("", "Build"),
+        ]).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![2]), &TaskId::new(vec![1, 1, 1])).unwrap();
+        tasks.mark_done(&TaskId::new(vec![1, 1, 1])).unwrap();
+        tasks.mark_done(&TaskId::new(vec![2])).unwrap();
+        assert_eq!(tasks.get(&TaskId::get_root_id()).unwrap().status, TaskStatus::Done);
+        assert_eq!(tasks.reopen(&TaskId::new(vec![1, 1])), Err(Error::CannotMarkTrunk(TaskId::new(vec![1, 1]))));
+
+        tasks.reopen(&TaskId::new(vec![1, 1, 1])).unwrap();
+        for id in [vec![1, 1, 1], vec![1, 1], vec![1], vec![]] {
+            assert_eq!(tasks.get(&TaskId::new(id)).unwrap().status, TaskStatus::InProgress);
+        }
+        // a done dependent stays done
+        assert_eq!(tasks.get(&TaskId::new(vec![2])).unwrap().status, TaskStatus::Done);
+        assert_eq!(tasks.completion_percentage(), 0.5);
+
+        tasks.reopen(&TaskId::new(vec![2])).unwrap();
+        assert_eq!(tasks.get(&TaskId::new(vec![2])).unwrap().status, TaskStatus::Blocked);
+        assert_eq!(tasks.get(&TaskId::new(vec![2])).unwrap().done_date(), None);
+        tasks.debug_assert_rollups();
+    }
+
+    #[test]
+    fn progress() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+            ("", "Build"),
+        ]).unwrap();
+        let (schema, build) = (TaskId::new(vec![1, 1]), TaskId::new(vec![2]));
+        tasks.set_planned_value(&schema, 4.0).unwrap();
+        tasks.set_planned_value(&build, 6.0).unwrap();
+        assert_eq!(tasks.set_progress(&TaskId::new(vec![1]), 0.5), Err(Error::CannotMarkTrunk(TaskId::new(vec![1]))));
+
+        tasks.set_progress(&schema, 0.25).unwrap();
+        tasks.set_progress(&build, 0.5).unwrap();
+        assert_eq!(tasks.earned_value(), 4.0);
+        assert_eq!(tasks.metrics_for(&TaskId::new(vec![1])).unwrap().earned_value, 1.0);
+        assert_eq!(tasks.completion_percentage(), 0.0);
+
+        // out of range values are clamped
+        tasks.set_progress(&schema, 1.5).unwrap();
+        assert_eq!(tasks.get(&schema).unwrap().progress(), 1.0);
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().status, TaskStatus::Done);
+        assert_eq!(tasks.earned_value(), 7.0);
+        tasks.set_progress(&schema, 0.5).unwrap();
+        assert_eq!(tasks.get(&schema).unwrap().status, TaskStatus::InProgress);
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().status, TaskStatus::InProgress);
+        assert_eq!(tasks.earned_value(), 5.0);
+        tasks.set_progress(&build, -1.0).unwrap();
+        assert_eq!(tasks.get(&build).unwrap().progress(), 0.0);
+        assert_eq!(tasks.earned_value(), 2.0);
+
+        // a task done at full progress has none left once reopened
+        tasks.set_progress(&build, 1.0).unwrap();
+        tasks.reopen(&build).unwrap();
+        assert_eq!(tasks.get(&build).unwrap().progress(), 0.0);
+        tasks.debug_assert_rollups();
+    }
+
+    #[test]
+    fn descendants() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                    ("1.1", "Tables"),
+                ("1", "Mockups"),
+            ("", "Build"),
+        ]).unwrap();
+        assert_eq!(tasks.descendants(&TaskId::get_root_id()).count(), tasks.len() - 1);
+        assert_eq!(
+            tasks.descendants(&TaskId::new(vec![1])).map(|task| task.name()).collect::<Vec<&str>>(),
+            ["Schema", "Tables", "Mockups"]);
+        assert_eq!(tasks.descendants(&TaskId::new(vec![2])).count(), 0);
+        assert_eq!(tasks.descendants(&TaskId::new(vec![3])).count(), 0);
+    }
+
+    #[test]
+    fn task_and_leaf_counts() {
+        let mut tasks = Tasks::new("Project");
+        assert_eq!((tasks.task_count(), tasks.leaf_count()), (1, 0));
+        assert_eq!(tasks.subtree_completion(&TaskId::get_root_id()), Ok(0.0));
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                ("1", "Mockups"),
+            ("", "Build"),
+                ("2", "Backend"),
+            ("", "Release"),
+        ]).unwrap();
+        assert_eq!((tasks.task_count(), tasks.leaf_count()), (7, 4));
+        tasks.set_actual_cost(&TaskId::new(vec![1, 2]), 1.0).unwrap();
+        assert_eq!(tasks.completion_percentage(), 1.0 / 4.0);
+    }
+
+    #[test]
+    fn ordered_leaf_ids() {
+        let mut tasks = Tasks::new("Project");
+        assert!(tasks.ordered_leaf_ids().is_empty());
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+                    ("1.1", "Tables"),
+                ("1", "Mockups"),
+            ("", "Build"),
+            ("", "Release"),
+                ("3", "Notes"),
+        ]).unwrap();
+        assert_eq!(tasks.ordered_leaf_ids(), vec![
+            TaskId::new(vec![1, 1, 1]),
+            TaskId::new(vec![1, 2]),
+            TaskId::new(vec![2]),
+            TaskId::new(vec![3, 1]),
+        ]);
+    }
+
+    #[test]
+    fn remove_drops_dependency_edges() {
+        let mut tasks = Tasks::new("Project");
+        let mut members = Members::new();
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+            ("", "Test"),
+            ("", "Release"),
+        ]).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![4]), &TaskId::new(vec![2])).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![4]), &TaskId::new(vec![1])).unwrap();
+
+        // "Test" takes over id 2 and "Release" moves to 3, which must not end up depending on "Test"
+        tasks.remove(&TaskId::new(vec![2]), &mut members).unwrap();
+        assert!(tasks.is_consistent());
+        assert_eq!(tasks.get(&TaskId::new(vec![3])).unwrap().name(), "Release");
+        assert_eq!(tasks.dependencies_of(&TaskId::new(vec![3])), Some(vec![TaskId::new(vec![1])]));
+        assert!(tasks.get(&TaskId::new(vec![2])).unwrap().dependency_for.is_empty());
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().dependency_for, HashSet::from([TaskId::new(vec![3])]));
+    }
+
+    #[test]
+    fn remove_keeps_edges_onto_shifted_tasks() {
+        let mut tasks = Tasks::new("Project");
+        let mut members = Members::new();
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+            ("", "Test"),
+            ("", "Release"),
+        ]).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![1]), &TaskId::new(vec![3])).unwrap();
+        tasks.add_dependency(&TaskId::new(vec![4]), &TaskId::new(vec![3])).unwrap();
+
+        tasks.remove(&TaskId::new(vec![2]), &mut members).unwrap();
+        assert!(tasks.is_consistent());
+        let test = tasks.get(&TaskId::new(vec![2])).unwrap();
+        assert_eq!(test.name(), "Test");
+        assert_eq!(test.dependency_for, HashSet::from([TaskId::new(vec![1]), TaskId::new(vec![3])]));
+        assert_eq!(tasks.dependencies_of(&TaskId::new(vec![1])), Some(vec![TaskId::new(vec![2])]));
+        assert_eq!(tasks.dependencies_of(&TaskId::new(vec![3])), Some(vec![TaskId::new(vec![2])]));
+    }
+
+    #[test]
+    fn malformed_ids_fail_gracefully() {
+        let mut tasks = Tasks::new("Project");
+        tasks.expand(&[
+            ("", "Design"),
+                ("1", "Schema"),
+        ]).unwrap();
+        tasks.set_planned_value(&TaskId::new(vec![1, 1]), 2.0).unwrap();
+        let missing = TaskId::new(vec![1, 7, 3]);
+
+        assert_eq!(tasks.set_planned_value(&missing, 1.0), Err(Error::TaskNotFound(missing.clone())));
+        assert_eq!(tasks.set_actual_cost(&missing, 1.0), Err(Error::TaskNotFound(missing.clone())));
+        // the ancestors that do exist are left alone
+        assert_eq!(tasks.apply_along_path(&missing, |task| task.planned_value += 1.0), Err(Error::TaskNotFound(TaskId::new(vec![1, 7]))));
+        assert_eq!(tasks.planned_value(), 2.0);
+        assert_eq!(tasks.get(&TaskId::new(vec![1])).unwrap().get_planned_value(), 2.0);
+        tasks.debug_assert_rollups();
+    }
+
+    #[test]
+    fn remove_keeps_members_in_sync() {
+        let mut tasks = Tasks::new("Project");
+        let mut members = Members::new();
+        tasks.expand(&[
+            ("", "Design"),
+            ("", "Build"),
+                ("2", "Backend"),
+            ("", "Test"),
+        ]).unwrap();
+        members.insert("alice".to_string()).unwrap();
+        members.insert("bob".to_string()).unwrap();
+        members.assign_task_to_member(TaskId::new(vec![2, 1]), "alice", &mut tasks).unwrap();
+        members.assign_task_to_member(TaskId::new(vec![3]), "bob", &mut tasks).unwrap();
+
+        tasks.remove(&TaskId::new(vec![1]), &mut members).unwrap();
+        let alice = members.get("alice").unwrap().task_ids().cloned().collect::<Vec<TaskId>>();
+        assert_eq!(alice, vec![TaskId::new(vec![1, 1])]);
+        assert!(tasks.get(&TaskId::new(vec![1, 1])).unwrap().has_member("alice"));
+        let partition = tasks.partition_by_member(&members);
+        assert_eq!(partition["alice"], tasks.tasks_for_member("alice").map(|task| task.id().clone()).collect::<Vec<TaskId>>());
+        assert_eq!(partition["bob"], vec![TaskId::new(vec![2])]);
+
+        members.remove_member_from_task(&TaskId::new(vec![1, 1]), "alice", &mut tasks).unwrap();
+        tasks.remove_subtree(&TaskId::new(vec![1]), &mut members).unwrap();
+        let bob = members.get("bob").unwrap().task_ids().cloned().collect::<Vec<TaskId>>();
+        assert_eq!(bob, vec![TaskId::new(vec![1])]);
+        assert_eq!(tasks.tasks_for_member("bob").map(|task| task.id().clone()).collect::<Vec<TaskId>>(), bob);
+    }
+
+    #[test]
+    fn root_of_empty_project() {
+        let mut tasks = Tasks::new("Project");
+        let root = TaskId::get_root_id();
+        assert_eq!(tasks.mark_done(&root), Err(Error::CannotMarkTrunk(root.clone())));
+        assert_eq!(tasks.set_progress(&root, 0.5), Err(Error::CannotMarkTrunk(root.clone())));
+        assert_eq!(tasks.reopen(&root), Err(Error::CannotMarkTrunk(root.clone())));
+        let updates = HashMap::from([(root.clone(), TaskStatus::Done)]);
+        assert_eq!(tasks.apply_status_updates(&updates), Ok(vec![root.clone()]));
+        assert_eq!(tasks.get(&root).unwrap().status, TaskStatus::InProgress);
+        assert_eq!(tasks.to_csv(&Members::new()), "id,name,status,planned_value,actual_cost\n");
     }
 }
@@ -0,0 +1,57 @@
+use serde::{Serialize, Deserialize};
+
+/// Reusable project outline, instantiated with `Project::from_template`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Template {
+    name: String,
+    entries: Vec<TemplateEntry>,
+}
+
+/// Task in a template: its parent id string (as used by `TaskExecution::expand`), name and
+/// planned value
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TemplateEntry {
+    parent: String,
+    name: String,
+    planned_value: f64,
+}
+
+impl Template {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item=&TemplateEntry> {
+        self.entries.iter()
+    }
+
+    pub fn add_entry(&mut self, parent: &str, name: &str, planned_value: f64) -> &mut Self {
+        self.entries.push(TemplateEntry {
+            parent: parent.to_string(),
+            name: name.to_string(),
+            planned_value,
+        });
+        self
+    }
+}
+
+impl TemplateEntry {
+    pub fn parent(&self) -> &str {
+        &self.parent
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn planned_value(&self) -> f64 {
+        self.planned_value
+    }
+}